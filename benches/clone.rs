@@ -0,0 +1,21 @@
+#[macro_use]
+extern crate criterion;
+extern crate heaparray;
+
+use criterion::Criterion;
+use heaparray::*;
+
+const LEN: usize = 1_000_000;
+
+fn clone(c: &mut Criterion) {
+    let array = HeapArray::new(LEN, |i| i as u64);
+    c.bench_function("clone u64", move |b| b.iter(|| array.clone()));
+}
+
+fn clone_copy(c: &mut Criterion) {
+    let array = HeapArray::new(LEN, |i| i as u64);
+    c.bench_function("clone_copy u64", move |b| b.iter(|| array.clone_copy()));
+}
+
+criterion_group!(benches, clone, clone_copy);
+criterion_main!(benches);
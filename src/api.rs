@@ -2,6 +2,8 @@
 
 pub use crate::api_prelude_rc::*;
 pub use crate::impls::FatPtrArray as HeapArray;
+pub use crate::impls::LabelledSlice;
 
 pub use crate::naive_rc::FpArcArray as ArcArray;
 pub use crate::naive_rc::FpRcArray as RcArray;
+pub use crate::naive_rc::FpWeakArcArray as WeakArcArray;
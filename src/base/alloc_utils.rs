@@ -1,14 +1,65 @@
 //! Contains pointer math and allocation utilities.
 #[cfg(feature = "no-std")]
 use crate::alloc::alloc;
-use const_utils::cond;
 use core::alloc::Layout;
 use core::mem::{align_of, size_of};
 #[cfg(not(feature = "no-std"))]
 use std::alloc;
 
+/// Interface to an allocator that `MemBlock` can allocate its memory with.
+///
+/// Implement this, along with `Default`, to allocate arrays with a custom
+/// allocator, like a bump allocator, and then pass it as the last type parameter
+/// of `FatArrayPtr` or `ThinArrayPtr`. `MemBlock::alloc_in` and
+/// `MemBlock::dealloc_in` can also be called directly from an implementation of
+/// `BaseArrayPtr`.
+///
+/// # Safety
+/// Implementations must follow the same rules as implementations of
+/// `core::alloc::GlobalAlloc`: `alloc` returns either a null pointer or a block
+/// of memory that fits `layout`, and `dealloc` is only called on blocks returned
+/// by `alloc` on the same allocator, with the same layout.
+pub unsafe trait RawAllocator {
+    /// Allocates a block of memory that fits `layout`, or returns a null pointer.
+    ///
+    /// # Safety
+    /// `layout` must have a non-zero size.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+    /// Deallocates the block of memory at `ptr`, which was allocated with `layout`.
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by `alloc` on this allocator with the same
+    /// layout, and can't be used afterwards.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+    /// Resizes the block of memory at `ptr` to `new_size` bytes, keeping its
+    /// alignment and its contents up to the smaller of the two sizes. Returns a
+    /// null pointer, and leaves the old block alone, if it can't be resized.
+    ///
+    /// The default implementation allocates a new block, copies the contents of
+    /// the old block into it, and deallocates the old block.
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by `alloc` on this allocator with the layout
+    /// `layout`, and `new_size` must be non-zero.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            let size = if layout.size() < new_size {
+                layout.size()
+            } else {
+                new_size
+            };
+            core::ptr::copy_nonoverlapping(ptr, new_ptr, size);
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
 /// Represents the global allocator. A hack while the allocator APIs are still
 /// very small.
+#[derive(Clone, Copy, Default)]
 pub struct Global;
 
 unsafe impl alloc::GlobalAlloc for Global {
@@ -20,8 +71,23 @@ unsafe impl alloc::GlobalAlloc for Global {
     }
 }
 
+unsafe impl RawAllocator for Global {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        alloc::alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        alloc::dealloc(ptr, layout);
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        alloc::realloc(ptr, layout, new_size)
+    }
+}
+
 /// Allocate a block of memory, and then coerce it to type `T`
-pub unsafe fn allocate<T>(layout: Layout, allocator: impl alloc::GlobalAlloc) -> *mut T {
+pub unsafe fn allocate<T, A>(layout: Layout, allocator: &A) -> *mut T
+where
+    A: RawAllocator,
+{
     allocator.alloc(layout) as *mut T
 }
 
@@ -29,21 +95,52 @@ pub unsafe fn allocate<T>(layout: Layout, allocator: impl alloc::GlobalAlloc) ->
 ///
 /// Completely ignores the type of the input pointer, so the layout
 /// needs to be correct.
-pub unsafe fn deallocate<T>(ptr: *mut T, layout: Layout, allocator: impl alloc::GlobalAlloc) {
+pub unsafe fn deallocate<T, A>(ptr: *mut T, layout: Layout, allocator: &A)
+where
+    A: RawAllocator,
+{
     allocator.dealloc(ptr as *mut u8, layout);
 }
 
 /// Get the size and alignment, in bytes, of a type repeated `repeat` many times.
+///
+/// # Panics
+/// Panics if the size overflows a `usize`.
 pub const fn size_align<T>(repeat: usize) -> (usize, usize) {
-    let align = align_of::<T>();
-    let size = size_of::<T>();
-    (size * repeat, align)
+    match checked_size_align::<T>(repeat) {
+        Some(size_align) => size_align,
+        None => panic!("Size of a repeated type overflowed a usize"),
+    }
+}
+
+/// Get the size and alignment, in bytes, of a type repeated `repeat` many times,
+/// or `None` if the size overflows a `usize`.
+pub const fn checked_size_align<T>(repeat: usize) -> Option<(usize, usize)> {
+    match size_of::<T>().checked_mul(repeat) {
+        Some(size) => Some((size, align_of::<T>())),
+        None => None,
+    }
 }
 
 /// Gets the aligned size of a type given a specific alignment
+///
+/// # Panics
+/// Panics if the aligned size overflows a `usize`.
 pub const fn aligned_size<T>(align: usize) -> usize {
+    match checked_aligned_size::<T>(align) {
+        Some(size) => size,
+        None => panic!("Aligned size of a type overflowed a usize"),
+    }
+}
+
+/// Gets the aligned size of a type given a specific alignment, or `None` if it
+/// overflows a `usize`.
+pub const fn checked_aligned_size<T>(align: usize) -> Option<usize> {
     let size = size_of::<T>();
     let off_by = size % align;
-    let adjusted_size = size + align - off_by;
-    cond(off_by == 0, size, adjusted_size)
+    if off_by == 0 {
+        Some(size)
+    } else {
+        size.checked_add(align - off_by)
+    }
 }
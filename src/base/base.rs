@@ -31,13 +31,17 @@ where
 /// `BaseArray` can't be safely iterated over, so this object can only be constructed
 /// via the unsafe method `BaseArray::into_iter`, which takes as a parameter an
 /// associated length.
+///
+/// Keeps track of which elements haven't been yielded using indices instead of
+/// pointers, so it works the same way when `E` is zero-sized.
 pub struct BaseArrayIter<E, L, P = NonNull<MemBlock<E, L>>>
 where
     P: BaseArrayPtr<E, L>,
 {
     array: BaseArray<E, L, P>,
-    current: *mut E,
-    end: *mut E,
+    len: usize,
+    front: usize,
+    back: usize,
 }
 
 impl<E, L, P> BaseArray<E, L, P>
@@ -70,6 +74,18 @@ where
         array
     }
 
+    /// Doesn't initialize anything in the array. Just allocates a block of memory,
+    /// returning an error if the block couldn't be allocated.
+    ///
+    /// # Safety
+    /// The label and elements of the array are uninitialized, so they must be
+    /// written to before they're read or dropped.
+    pub unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        let mut array = Self::from_ptr(P::try_alloc(len)?);
+        array.data._init();
+        Ok(array)
+    }
+
     /// Doesn't initialize the elements of the array.
     pub unsafe fn new_lazy(label: L, len: usize) -> Self {
         let mut array = Self::alloc(len);
@@ -80,18 +96,55 @@ where
     /// Creates a new array of size `len`.
     ///
     /// Initializes all elements using the given function, and initializes the
-    /// label with the provided value.
-    pub fn new<F>(label: L, len: usize, mut func: F) -> Self
+    /// label with the provided value. If `func` panics, the label and the
+    /// elements initialized so far are dropped, and the block is deallocated.
+    pub fn new<F>(label: L, len: usize, func: F) -> Self
     where
         F: FnMut(&mut L, usize) -> E,
     {
-        let array = unsafe { Self::new_lazy(label, len) };
-        for i in 0..len {
-            unsafe {
-                ptr::write(array.data.elem_ptr(i), func(&mut *array.data.lbl_ptr(), i));
-            }
+        unsafe {
+            let mut array = Self::new_lazy(label, len);
+            array.init_elements(len, func);
+            array
         }
-        array
+    }
+
+    /// Creates a new array of size `len`, returning an error if the block
+    /// couldn't be allocated.
+    ///
+    /// Initializes all elements using the given function, and initializes the
+    /// label with the provided value. If `func` panics, the label and the
+    /// elements initialized so far are dropped, and the block is deallocated.
+    pub fn try_new<F>(label: L, len: usize, func: F) -> Result<Self, TryReserveError>
+    where
+        F: FnMut(&mut L, usize) -> E,
+    {
+        unsafe {
+            let mut array = Self::try_alloc(len)?;
+            ptr::write(array.get_label_mut(), label);
+            array.init_elements(len, func);
+            Ok(array)
+        }
+    }
+
+    /// Initializes elements `0..len` using `func`. If `func` panics, drops the
+    /// label and the elements initialized so far, and deallocates the block.
+    unsafe fn init_elements<F>(&mut self, len: usize, mut func: F)
+    where
+        F: FnMut(&mut L, usize) -> E,
+    {
+        let mut guard = InitGuard {
+            array: self,
+            len,
+            initialized: 0,
+        };
+        while guard.initialized < len {
+            let data = &guard.array.data;
+            let elem = func(&mut *data.lbl_ptr(), guard.initialized);
+            ptr::write(data.elem_ptr(guard.initialized), elem);
+            guard.initialized += 1;
+        }
+        mem::forget(guard);
     }
 
     /// Runs destructor code for elements and for label, then deallocates block.
@@ -116,6 +169,40 @@ where
         self.data.dealloc(len);
     }
 
+    /// Panics if the element type `T` doesn't have the same size and alignment
+    /// as the element type `E` of this array.
+    ///
+    /// Casting to an element type with a different layout changes where each
+    /// element lives in the block, so it's almost always a bug. With the
+    /// `checked-casts` feature enabled, this check is run by `cast_into`,
+    /// `cast_ref`, and `cast_mut`.
+    pub fn assert_cast_compatible<T>(&self) {
+        assert!(
+            mem::size_of::<E>() == mem::size_of::<T>()
+                && mem::align_of::<E>() == mem::align_of::<T>(),
+            "Cannot cast array elements of (size, align) = ({}, {}) to elements \
+             of (size, align) = ({}, {})",
+            mem::size_of::<E>(),
+            mem::align_of::<E>(),
+            mem::size_of::<T>(),
+            mem::align_of::<T>()
+        );
+    }
+
+    /// Resizes the block of this array to hold `new_len` elements, keeping the
+    /// label and the first `min(old_len, new_len)` elements. The block is
+    /// resized in place if the pointer type and allocator allow it.
+    ///
+    /// # Safety
+    /// The underlying array must be at least length `old_len`. Elements at
+    /// indices `new_len..old_len` aren't dropped, and elements at indices
+    /// `old_len..new_len` aren't initialized.
+    pub unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        self.data._drop();
+        self.data.realloc(old_len, new_len);
+        self.data._init();
+    }
+
     /// Cast this array into a different array.
     ///
     /// Doesn't alter the length information of the array at all, or perform
@@ -124,6 +211,8 @@ where
     where
         Q: BaseArrayPtr<T, L>,
     {
+        #[cfg(feature = "checked-casts")]
+        self.assert_cast_compatible::<T>();
         BaseArray::<T, L, Q>::from_ptr(self.data.cast::<T, L, Q>())
     }
 
@@ -135,6 +224,8 @@ where
     where
         Q: BaseArrayPtr<T, L>,
     {
+        #[cfg(feature = "checked-casts")]
+        self.assert_cast_compatible::<T>();
         &*(self as *const BaseArray<E, L, P> as *const BaseArray<T, L, Q>)
     }
 
@@ -147,6 +238,8 @@ where
     where
         Q: BaseArrayPtr<T, L>,
     {
+        #[cfg(feature = "checked-casts")]
+        self.assert_cast_compatible::<T>();
         &mut *(self as *mut BaseArray<E, L, P> as *mut BaseArray<T, L, Q>)
     }
 
@@ -218,13 +311,37 @@ where
     }
 
     /// Returns an iterator into this array, consuming the array in the process.
-    pub unsafe fn into_iter(mut self, len: usize) -> BaseArrayIter<E, L, P> {
-        let current = self.get_mut(0) as *mut E;
-        let end = current.add(len);
+    pub unsafe fn into_iter(self, len: usize) -> BaseArrayIter<E, L, P> {
         BaseArrayIter {
             array: self,
-            current,
-            end,
+            len,
+            front: 0,
+            back: len,
+        }
+    }
+}
+
+/// Cleans up a partially initialized array if its initialization panics.
+struct InitGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    array: &'a mut BaseArray<E, L, P>,
+    len: usize,
+    initialized: usize,
+}
+
+impl<'a, E, L, P> Drop for InitGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.array.get_label_mut());
+            for i in 0..self.initialized {
+                ptr::drop_in_place(self.array.get_ptr_mut(i));
+            }
+            self.array.drop_lazy(self.len);
         }
     }
 }
@@ -242,31 +359,74 @@ where
     }
 }
 
+impl<E, L, P> BaseArray<E, L, P>
+where
+    E: Copy,
+    L: Clone,
+    P: BaseArrayPtr<E, L>,
+{
+    /// Clones the label of this array, and copies its elements into a new array
+    /// of the same size with a single `ptr::copy_nonoverlapping`.
+    ///
+    /// # Safety
+    /// The underlying array must be at least length `len`, and its label and
+    /// first `len` elements must be initialized.
+    pub unsafe fn clone_copy(&self, len: usize) -> Self {
+        let mut array = Self::new_lazy(self.get_label().clone(), len);
+        ptr::copy_nonoverlapping(self.get_ptr(0), array.get_ptr_mut(0), len);
+        array
+    }
+}
+
 impl<E, L, P> Iterator for BaseArrayIter<E, L, P>
 where
     P: BaseArrayPtr<E, L>,
 {
     type Item = E;
     fn next(&mut self) -> Option<E> {
-        if self.current == self.end {
+        if self.front == self.back {
             None
         } else {
-            unsafe {
-                let out = Some(ptr::read(self.current));
-                self.current = self.current.add(1);
-                out
-            }
+            let out = unsafe { ptr::read(self.array.get_ptr(self.front)) };
+            self.front += 1;
+            Some(out)
         }
     }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
 }
 
+impl<E, L, P> DoubleEndedIterator for BaseArrayIter<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn next_back(&mut self) -> Option<E> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 1;
+            Some(unsafe { ptr::read(self.array.get_ptr(self.back)) })
+        }
+    }
+}
+
+impl<E, L, P> ExactSizeIterator for BaseArrayIter<E, L, P> where P: BaseArrayPtr<E, L> {}
+
+/// Drops the label and the elements that haven't been yielded, then deallocates
+/// the block.
 impl<E, L, P> Drop for BaseArrayIter<E, L, P>
 where
     P: BaseArrayPtr<E, L>,
 {
     fn drop(&mut self) {
-        let begin = self.array.get_ptr_mut(0) as usize;
-        let len = ((self.end as usize) - begin) / mem::size_of::<E>();
-        unsafe { self.array.drop(len) }
+        unsafe {
+            ptr::drop_in_place(self.array.get_label_mut());
+            for i in self.front..self.back {
+                ptr::drop_in_place(self.array.get_ptr_mut(i));
+            }
+            self.array.drop_lazy(self.len);
+        }
     }
 }
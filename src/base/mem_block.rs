@@ -78,31 +78,73 @@ impl<E, L> MemBlock<E, L> {
     /// Get the maximum length of a `MemBlock`, based on the types that it contains.
     ///
     /// This function is used to maintain the invariant that all `MemBlock` instances
-    /// are of size (in bytes) less than or equal to `core::isize::MAX`.
+    /// are of size (in bytes) less than or equal to `core::isize::MAX`, even after
+    /// their size is padded to a multiple of their alignment.
     pub const fn max_len() -> usize {
         let max_len = core::isize::MAX as usize;
         let max_len_calc = {
             let (esize, ealign) = size_align::<E>(1);
             let lsize = aligned_size::<L>(ealign);
-            safe_div(max_len - lsize, esize)
+            let block_align = max(mem::align_of::<L>(), ealign);
+            safe_div(max_len - (block_align - 1) - lsize, esize)
         };
         cond(mem::size_of::<E>() == 0, max_len, max_len_calc)
     }
 
+    /// Get the offset, in bytes, of the first element from the start of the block.
+    pub const fn elements_offset() -> usize {
+        aligned_size::<L>(mem::align_of::<E>())
+    }
+
+    /// Get the alignment, in bytes, that the first element of a non-empty block
+    /// is guaranteed to have.
+    ///
+    /// This is at least `align_of::<E>()`, but can be larger if the alignment of
+    /// the label is larger and the label is small enough.
+    pub const fn elements_align() -> usize {
+        let block_align = max(mem::align_of::<L>(), mem::align_of::<E>());
+        let offset = Self::elements_offset();
+        if offset == 0 {
+            block_align
+        } else {
+            let offset_align = 1 << offset.trailing_zeros();
+            if offset_align < block_align {
+                offset_align
+            } else {
+                block_align
+            }
+        }
+    }
+
     /// Get size and alignment of the memory that a block of length `len` would need.
     ///
     /// Returns a tuple in the form `(size, align)`
+    ///
+    /// # Panics
+    /// Panics if the size would overflow a `usize`; use `checked_memory_layout`
+    /// to handle that case instead.
     pub const fn memory_layout(len: usize) -> (usize, usize) {
+        match Self::checked_memory_layout(len) {
+            Some(layout) => layout,
+            None => panic!("MemBlock layout size overflowed a usize"),
+        }
+    }
+
+    /// Get size and alignment of the memory that a block of length `len` would
+    /// need, or `None` if the size would overflow a `usize`.
+    pub const fn checked_memory_layout(len: usize) -> Option<(usize, usize)> {
         let (l_size, l_align) = size_align::<L>(1);
-        let (calc_size, calc_align) = {
-            let (dsize, dalign) = size_align::<E>(len);
-            let l_size = aligned_size::<L>(dalign);
-            (l_size + dsize, max(l_align, dalign))
+        if len == 0 {
+            return Some((l_size, l_align));
+        }
+        let (e_size, e_align) = match checked_size_align::<E>(len) {
+            Some(size_align) => size_align,
+            None => return None,
         };
-        (
-            cond(len == 0, l_size, calc_size),
-            cond(len == 0, l_align, calc_align),
-        )
+        match aligned_size::<L>(e_align).checked_add(e_size) {
+            Some(size) => Some((size, max(l_align, e_align))),
+            None => None,
+        }
     }
 }
 
@@ -118,10 +160,66 @@ fn check_len<E, L>(len: usize) {
     }
 }
 
-/// Get the memory layout of a `MemBlock<E, L>` of length `len`
+/// Error returned by fallible allocation methods, like `BaseArrayPtr::try_alloc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested length was larger than the maximum length that can be
+    /// allocated.
+    LengthOverflow {
+        /// The requested length
+        len: usize,
+        /// The maximum length that can be allocated
+        max_len: usize,
+    },
+    /// The allocator returned a null pointer.
+    AllocError {
+        /// The layout of the allocation that failed
+        layout: Layout,
+    },
+}
+
+impl core::fmt::Display for TryReserveError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            TryReserveError::LengthOverflow { len, max_len } => write!(
+                formatter,
+                "Length {} is larger than the maximum length {}",
+                len, max_len
+            ),
+            TryReserveError::AllocError { layout } => write!(
+                formatter,
+                "Allocator failed to allocate memory of (size, align) = ({}, {})",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+/// Get the memory layout of a `MemBlock<E, L>` of length `len`, or an error if
+/// the block would be too big.
+fn try_get_layout<E, L>(len: usize) -> Result<Layout, TryReserveError> {
+    let max_len = MemBlock::<E, L>::max_len();
+    if len > max_len {
+        return Err(TryReserveError::LengthOverflow { len, max_len });
+    }
+    let overflow = TryReserveError::LengthOverflow { len, max_len };
+    let (size, align) = MemBlock::<E, L>::checked_memory_layout(len).ok_or(overflow)?;
+    Layout::from_size_align(size, align).map_err(|_| overflow)
+}
+
+/// Get the memory layout of a `MemBlock<E, L>` of length `len`. The size is
+/// always checked for overflow, even with `mem-block-skip-size-check` enabled,
+/// since a size that wraps around would make the allocation too small.
 fn get_layout<E, L>(len: usize) -> Layout {
     check_len::<E, L>(len);
-    let (size, align) = MemBlock::<E, L>::memory_layout(len);
+    let (size, align) = match MemBlock::<E, L>::checked_memory_layout(len) {
+        Some(layout) => layout,
+        None => panic!(
+            "MemBlock of length {} is invalid: layout size overflow",
+            len
+        ),
+    };
     if cfg!(feature = "mem-block-skip-layout-check") {
         unsafe { Layout::from_size_align_unchecked(size, align) }
     } else {
@@ -138,10 +236,33 @@ fn get_layout<E, L>(len: usize) -> Layout {
     }
 }
 
-unsafe impl<E, L> BaseArrayPtr<E, L> for *mut MemBlock<E, L> {
-    unsafe fn alloc(len: usize) -> Self {
+impl<E, L> MemBlock<E, L> {
+    /// Returns a non-null pointer that stands in for a block of size 0, which
+    /// can't be allocated. It's aligned for both the label and the elements,
+    /// which are all zero-sized and start at offset 0.
+    fn dangling() -> *mut Self {
+        max(mem::align_of::<L>(), mem::align_of::<E>()) as *mut Self
+    }
+
+    /// Allocates a block of length `len` using `allocator`. Blocks of size 0,
+    /// like empty arrays with a zero-sized label, aren't allocated at all.
+    ///
+    /// # Panics
+    /// Panics if the block would be too big, or if the allocator returns a null
+    /// pointer; see the documentation on features above for how to turn these
+    /// checks off.
+    ///
+    /// # Safety
+    /// The label and elements of the block are uninitialized.
+    pub unsafe fn alloc_in<A>(len: usize, allocator: &A) -> *mut Self
+    where
+        A: RawAllocator,
+    {
         let layout = get_layout::<E, L>(len);
-        let ptr = allocate(layout, Global);
+        if layout.size() == 0 {
+            return Self::dangling();
+        }
+        let ptr = allocate(layout, allocator);
         if cfg!(feature = "mem-block-skip-ptr-check") {
             ptr
         } else {
@@ -153,9 +274,114 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for *mut MemBlock<E, L> {
             ptr
         }
     }
-    unsafe fn dealloc(&mut self, len: usize) {
+
+    /// Allocates a block of length `len` using `allocator`, returning an error
+    /// instead of panicking if it can't be allocated.
+    ///
+    /// # Safety
+    /// The label and elements of the block are uninitialized.
+    pub unsafe fn try_alloc_in<A>(len: usize, allocator: &A) -> Result<*mut Self, TryReserveError>
+    where
+        A: RawAllocator,
+    {
+        let layout = try_get_layout::<E, L>(len)?;
+        if layout.size() == 0 {
+            return Ok(Self::dangling());
+        }
+        let ptr: *mut Self = allocate(layout, allocator);
+        if ptr.is_null() {
+            Err(TryReserveError::AllocError { layout })
+        } else {
+            Ok(ptr)
+        }
+    }
+
+    /// Deallocates the block at `ptr`, of length `len`, using `allocator`,
+    /// without running any destructors.
+    ///
+    /// # Safety
+    /// `ptr` must have been allocated by `allocator` with the same length.
+    pub unsafe fn dealloc_in<A>(ptr: *mut Self, len: usize, allocator: &A)
+    where
+        A: RawAllocator,
+    {
         let layout = get_layout::<E, L>(len);
-        deallocate(*self, layout, Global);
+        if layout.size() != 0 {
+            deallocate(ptr, layout, allocator);
+        }
+    }
+
+    /// Resizes the block at `ptr` from length `old_len` to length `new_len`
+    /// using `allocator`, keeping the label and the first `min(old_len, new_len)`
+    /// elements. Returns a pointer to the resized block, which may have moved.
+    ///
+    /// The offset of the elements only depends on the types of the label and
+    /// elements, so it's the same for both lengths. The block is resized in
+    /// place when the allocator allows it, unless the alignment of the block
+    /// changes, which can happen when one of the lengths is 0; then a new block
+    /// is allocated and the contents are copied over.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as `alloc_in`.
+    ///
+    /// # Safety
+    /// `ptr` must have been allocated by `allocator` with length `old_len`, and
+    /// can't be used afterwards.
+    pub unsafe fn realloc_in<A>(
+        ptr: *mut Self,
+        old_len: usize,
+        new_len: usize,
+        allocator: &A,
+    ) -> *mut Self
+    where
+        A: RawAllocator,
+    {
+        let old_layout = get_layout::<E, L>(old_len);
+        let new_layout = get_layout::<E, L>(new_len);
+        if old_layout.align() != new_layout.align()
+            || old_layout.size() == 0
+            || new_layout.size() == 0
+        {
+            let new_ptr = Self::alloc_in(new_len, allocator);
+            let size = core::cmp::min(old_layout.size(), new_layout.size());
+            core::ptr::copy_nonoverlapping(ptr as *const u8, new_ptr as *mut u8, size);
+            Self::dealloc_in(ptr, old_len, allocator);
+            return new_ptr;
+        }
+        let new_ptr = allocator.realloc(ptr as *mut u8, old_layout, new_layout.size()) as *mut Self;
+        if cfg!(not(feature = "mem-block-skip-ptr-check")) {
+            assert!(
+                !new_ptr.is_null(),
+                "Reallocated a null pointer.\
+                 You may be out of memory.",
+            );
+        }
+        new_ptr
+    }
+
+    /// Resizes the block at `ptr` from length `old_len` to length `new_len`
+    /// using the global allocator. See `realloc_in` for more details.
+    ///
+    /// # Safety
+    /// `ptr` must have been allocated by the global allocator with length
+    /// `old_len`, and can't be used afterwards.
+    pub unsafe fn realloc(ptr: *mut Self, old_len: usize, new_len: usize) -> *mut Self {
+        Self::realloc_in(ptr, old_len, new_len, &Global)
+    }
+}
+
+unsafe impl<E, L> BaseArrayPtr<E, L> for *mut MemBlock<E, L> {
+    unsafe fn alloc(len: usize) -> Self {
+        MemBlock::alloc_in(len, &Global)
+    }
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        MemBlock::try_alloc_in(len, &Global)
+    }
+    unsafe fn dealloc(&mut self, len: usize) {
+        MemBlock::dealloc_in(*self, len, &Global)
+    }
+    unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        *self = MemBlock::realloc(*self, old_len, new_len);
     }
     unsafe fn from_ptr(ptr: *mut u8) -> Self {
         ptr as *mut MemBlock<E, L>
@@ -171,8 +397,7 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for *mut MemBlock<E, L> {
     }
     fn elem_ptr(&self, idx: usize) -> *mut E {
         check_len::<E, L>(idx + 1);
-        let e_align = mem::align_of::<E>();
-        let lsize = aligned_size::<L>(e_align);
+        let lsize = MemBlock::<E, L>::elements_offset();
         let element = unsafe { (*self as *mut u8).add(lsize) as *mut E };
         unsafe { element.add(idx) }
     }
@@ -182,9 +407,15 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for NonNull<MemBlock<E, L>> {
     unsafe fn alloc(len: usize) -> Self {
         NonNull::new_unchecked(MutMB::alloc(len))
     }
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        MutMB::try_alloc(len).map(|ptr| NonNull::new_unchecked(ptr))
+    }
     unsafe fn dealloc(&mut self, len: usize) {
         self.clone().as_ptr().dealloc(len)
     }
+    unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        *self = NonNull::new_unchecked(MemBlock::realloc(self.as_ptr(), old_len, new_len));
+    }
     unsafe fn from_ptr(ptr: *mut u8) -> Self {
         NonNull::new_unchecked(MutMB::from_ptr(ptr))
     }
@@ -206,6 +437,9 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for AtomicPtr<MemBlock<E, L>> {
     unsafe fn alloc(len: usize) -> Self {
         AtomicPtr::new(MutMB::alloc(len))
     }
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        MutMB::try_alloc(len).map(AtomicPtr::new)
+    }
     unsafe fn dealloc(&mut self, len: usize) {
         self.load(Ordering::Acquire).dealloc(len)
     }
@@ -7,6 +7,7 @@ mod base;
 mod mem_block;
 mod traits;
 
+pub use alloc_utils::{Global, RawAllocator};
 pub use base::{BaseArray, BaseArrayIter};
-pub use mem_block::MemBlock;
+pub use mem_block::{MemBlock, TryReserveError};
 pub use traits::*;
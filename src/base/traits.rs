@@ -1,5 +1,7 @@
 //! Defines `BaseArrayPtr`, the interface `BaseArray` uses when defining methods.
 
+use super::mem_block::{MemBlock, TryReserveError};
+
 /// Trait representing an unsafe reference to an array.
 ///
 /// Should be the same size as the underlying pointer.
@@ -57,10 +59,49 @@ pub unsafe trait BaseArrayPtr<E, L>: Sized {
     /// initializing it
     unsafe fn alloc(len: usize) -> Self;
 
+    /// Allocate the memory necessary for a new instance of `len` elements, without
+    /// initializing it, returning an error instead of panicking if the memory
+    /// can't be allocated.
+    ///
+    /// The default implementation only checks `len` against `Self::max_len()`
+    /// before calling `Self::alloc`.
+    ///
+    /// # Safety
+    /// The memory returned is uninitialized.
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        let max_len = Self::max_len();
+        if len > max_len {
+            Err(TryReserveError::LengthOverflow { len, max_len })
+        } else {
+            Ok(Self::alloc(len))
+        }
+    }
+
     /// Deallocate the memory for an instance of `len` elements, without running
     /// destructors
     unsafe fn dealloc(&mut self, len: usize);
 
+    /// Resizes the memory of an instance of `len` elements to hold `new_len`
+    /// elements, keeping the label and the first `min(old_len, new_len)`
+    /// elements. Doesn't run any constructors or destructors.
+    ///
+    /// The default implementation allocates a new instance, copies the label and
+    /// elements into it, and deallocates this instance.
+    ///
+    /// # Safety
+    /// This instance must have been allocated with length `old_len`.
+    unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        let new = Self::alloc(new_len);
+        core::ptr::copy_nonoverlapping(self.lbl_ptr(), new.lbl_ptr(), 1);
+        core::ptr::copy_nonoverlapping(
+            self.elem_ptr(0),
+            new.elem_ptr(0),
+            core::cmp::min(old_len, new_len),
+        );
+        self.dealloc(old_len);
+        *self = new;
+    }
+
     /// Creates a new reference of this type without doing any checks.
     ///
     /// # Safety
@@ -88,6 +129,37 @@ pub unsafe trait BaseArrayPtr<E, L>: Sized {
     /// initialized element at that location.
     fn elem_ptr(&self, idx: usize) -> *mut E;
 
+    /// Returns the maximum length of an array that this pointer type can allocate.
+    ///
+    /// Defaults to the maximum length of a `MemBlock<E, L>`.
+    fn max_len() -> usize {
+        MemBlock::<E, L>::max_len()
+    }
+
+    /// Returns the size and alignment, in bytes, of the memory block of an
+    /// instance of `len` elements, as a tuple in the form `(size, align)`.
+    ///
+    /// Defaults to the memory layout of a `MemBlock<E, L>`.
+    fn memory_layout(len: usize) -> (usize, usize) {
+        MemBlock::<E, L>::memory_layout(len)
+    }
+
+    /// Returns the offset, in bytes, of the first element from the start of the
+    /// memory block.
+    ///
+    /// Defaults to the elements offset of a `MemBlock<E, L>`.
+    fn elements_offset() -> usize {
+        MemBlock::<E, L>::elements_offset()
+    }
+
+    /// Returns the alignment, in bytes, that the first element of a non-empty
+    /// array is guaranteed to have.
+    ///
+    /// Defaults to the elements alignment of a `MemBlock<E, L>`.
+    fn elements_align() -> usize {
+        MemBlock::<E, L>::elements_align()
+    }
+
     /// Initializes fields at construction
     ///
     /// Note that in `BaseArray` this will be run *before* any other initialization
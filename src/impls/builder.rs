@@ -0,0 +1,134 @@
+//! Contains the definition for `ArrayBuilder`, which fills in the elements of
+//! an array in any order.
+
+use super::generic::*;
+use super::p_types::FatArrayPtr;
+use crate::base::BaseArray;
+use crate::prelude::*;
+use core::ptr;
+
+const WORD_BITS: usize = 64;
+
+/// Builder for an array whose elements can be set in any order.
+///
+/// Created using `SafeArray::builder`. The builder keeps track of which
+/// elements have been set, and `build` only returns the finished array once
+/// every element has been. If the builder is dropped before then, the label and
+/// the elements that were set are dropped.
+///
+/// ```rust
+/// # use heaparray::*;
+/// let mut builder = HeapArray::builder("label", 3);
+/// builder.set(2, 'c');
+/// builder.set(0, 'a');
+/// assert!(builder.build().err() == Some(vec![1]));
+///
+/// let mut builder = HeapArray::builder("label", 3);
+/// for (i, c) in ['c', 'b', 'a'].iter().enumerate() {
+///     builder.set(2 - i, *c);
+/// }
+/// let array = builder.build().unwrap();
+/// assert!(array.as_slice() == &['a', 'b', 'c']);
+/// ```
+pub struct ArrayBuilder<E, L, P = FatArrayPtr<E, L>>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    data: BaseArray<E, L, P>,
+    len: usize,
+    initialized: Vec<u64>,
+}
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Creates a builder for an array of length `len` with the label `label`,
+    /// whose elements can then be set in any order.
+    pub fn builder(label: L, len: usize) -> ArrayBuilder<E, L, P> {
+        ArrayBuilder {
+            data: unsafe { BaseArray::new_lazy(label, len) },
+            len,
+            initialized: (0..len.div_ceil(WORD_BITS)).map(|_| 0).collect(),
+        }
+    }
+}
+
+impl<E, L, P> ArrayBuilder<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Returns the length of the array being built.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the array being built has a length of 0.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether the element at index `idx` has been set.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds.
+    pub fn is_set(&self, idx: usize) -> bool {
+        self.check_index(idx);
+        self.initialized[idx / WORD_BITS] & (1 << (idx % WORD_BITS)) != 0
+    }
+
+    /// Sets the element at index `idx` to `value`, dropping the element that
+    /// was there if it was already set.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds.
+    pub fn set(&mut self, idx: usize, value: E) {
+        if self.is_set(idx) {
+            unsafe { *self.data.get_mut(idx) = value };
+        } else {
+            unsafe { ptr::write(self.data.get_ptr_mut(idx), value) };
+            self.initialized[idx / WORD_BITS] |= 1 << (idx % WORD_BITS);
+        }
+    }
+
+    /// Returns the finished array if every element has been set. Otherwise,
+    /// drops the label and the elements that were set, and returns the indices
+    /// of the elements that weren't, in order.
+    pub fn build(self) -> Result<SafeArray<E, L, P>, Vec<usize>> {
+        let missing: Vec<usize> = (0..self.len).filter(|&i| !self.is_set(i)).collect();
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        let mut builder = mem::ManuallyDrop::new(self);
+        unsafe {
+            ptr::drop_in_place(&mut builder.initialized);
+            Ok(SafeArray::from_base(ptr::read(&builder.data), builder.len))
+        }
+    }
+
+    fn check_index(&self, idx: usize) {
+        assert!(
+            idx < self.len,
+            "Index {} is out of bounds for an array builder of length {}",
+            idx,
+            self.len
+        );
+    }
+}
+
+impl<E, L, P> Drop for ArrayBuilder<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.data.get_label_mut());
+            for i in 0..self.len {
+                if self.is_set(i) {
+                    ptr::drop_in_place(self.data.get_ptr_mut(i));
+                }
+            }
+            self.data.drop_lazy(self.len);
+        }
+    }
+}
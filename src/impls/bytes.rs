@@ -0,0 +1,60 @@
+//! Contains methods for searching arrays of bytes.
+
+use super::generic::*;
+use crate::prelude::*;
+
+const WORD_BYTES: usize = core::mem::size_of::<usize>();
+
+/// A word with every byte set to `0x01`.
+const LO_BITS: usize = usize::MAX / 0xff;
+
+/// A word with every byte set to `0x80`.
+const HI_BITS: usize = LO_BITS * 0x80;
+
+/// Returns whether any of the bytes of `word` is zero. This is exact; it never
+/// returns `true` for a word without a zero byte.
+fn has_zero_byte(word: usize) -> bool {
+    word.wrapping_sub(LO_BITS) & !word & HI_BITS != 0
+}
+
+/// Returns the index of the first byte in `bytes` equal to `needle`, scanning a
+/// word at a time through the aligned middle of the slice.
+fn find_byte(bytes: &[u8], needle: u8) -> Option<usize> {
+    let repeated = LO_BITS * needle as usize;
+    let (prefix, words, _) = unsafe { bytes.align_to::<usize>() };
+    if let Some(idx) = prefix.iter().position(|&byte| byte == needle) {
+        return Some(idx);
+    }
+    let mut offset = prefix.len();
+    for &word in words {
+        if has_zero_byte(word ^ repeated) {
+            break;
+        }
+        offset += WORD_BYTES;
+    }
+    bytes[offset..]
+        .iter()
+        .position(|&byte| byte == needle)
+        .map(|idx| offset + idx)
+}
+
+impl<L, P> SafeArray<u8, L, P>
+where
+    P: SafeArrayPtr<u8, L>,
+{
+    /// Returns the index of the first byte in this array equal to `needle`, or
+    /// `None` if there isn't one.
+    ///
+    /// Compares a whole word of bytes at a time, so it's faster than searching
+    /// with `iter().position()` on long arrays.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u8, ()>::new(11, |i| b"hello world"[i]);
+    /// assert!(array.find_byte(b'o') == Some(4));
+    /// assert!(array.find_byte(b'z').is_none());
+    /// ```
+    pub fn find_byte(&self, needle: u8) -> Option<usize> {
+        find_byte(self.as_slice(), needle)
+    }
+}
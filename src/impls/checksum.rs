@@ -0,0 +1,71 @@
+//! Contains arrays whose label is a checksum of their elements.
+
+use super::generic::*;
+use crate::prelude::*;
+use core::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher, whose entire state is the current digest; this lets an
+/// array's `u64` label act as the state of a rolling checksum.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn roll<E: Hash>(checksum: &mut u64, elem: &E) {
+    let mut hasher = Fnv(*checksum);
+    elem.hash(&mut hasher);
+    *checksum = hasher.finish();
+}
+
+impl<E, P> SafeArray<E, u64, P>
+where
+    P: SafeArrayPtr<E, u64>,
+    E: Hash,
+{
+    /// Creates a new array, initializing its elements using `func`, and its label
+    /// to a checksum of those elements, which is updated as each element is
+    /// produced.
+    ///
+    /// The checksum isn't updated when elements are changed afterwards; use
+    /// `verify_checksum` to check whether the elements still match it.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::with_checksum(10, |i| i * i);
+    /// assert!(array.verify_checksum());
+    /// array[3] = 0;
+    /// assert!(!array.verify_checksum());
+    /// ```
+    pub fn with_checksum<F>(len: usize, mut func: F) -> Self
+    where
+        F: FnMut(usize) -> E,
+    {
+        Self::with_label(FNV_OFFSET_BASIS, len, |checksum, idx| {
+            let elem = func(idx);
+            roll(checksum, &elem);
+            elem
+        })
+    }
+
+    /// Recomputes the checksum of the elements in this array, and returns whether
+    /// it matches the label.
+    pub fn verify_checksum(&self) -> bool {
+        let mut checksum = FNV_OFFSET_BASIS;
+        for elem in self {
+            roll(&mut checksum, elem);
+        }
+        checksum == *self.get_label()
+    }
+}
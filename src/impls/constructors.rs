@@ -0,0 +1,465 @@
+//! Additional constructors for `SafeArray`.
+
+use super::generic::*;
+use super::p_types::{FatArrayPtr, FatPtrArray};
+use crate::base::{BaseArray, BaseArrayPtr, MemBlock};
+use crate::prelude::*;
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Creates a new array with no elements, whose label is `label`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u32, &str>::empty_with_label("label");
+    /// assert!(array.len() == 0);
+    /// assert!(*array.get_label() == "label");
+    /// ```
+    pub fn empty_with_label(label: L) -> Self {
+        unsafe { Self::from_base(BaseArray::new_lazy(label, 0), 0) }
+    }
+
+    /// Creates a new array of length `len` whose element at index `i` is
+    /// `elem(i)`, and then computes its label by calling `label` on the finished
+    /// elements.
+    ///
+    /// The elements are written directly into the new array, and the label is
+    /// left uninitialized until `label` returns; if either function panics,
+    /// only the elements created so far are dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::with_elements_then_label(4, |i| i * 2, |elements| {
+    ///     elements.iter().sum::<usize>()
+    /// });
+    /// assert!(*array.get_label() == 12);
+    /// assert!(array.as_slice() == &[0, 2, 4, 6]);
+    /// ```
+    pub fn with_elements_then_label<F, G>(len: usize, mut elem: F, label: G) -> Self
+    where
+        F: FnMut(usize) -> E,
+        G: FnOnce(&[E]) -> L,
+    {
+        let mut guard = UnlabelledGuard {
+            data: unsafe { BaseArray::alloc(len) },
+            len,
+            initialized: 0,
+        };
+        while guard.initialized < len {
+            let value = elem(guard.initialized);
+            unsafe { ptr::write(guard.data.get_ptr_mut(guard.initialized), value) };
+            guard.initialized += 1;
+        }
+        let label = label(unsafe { guard.data.as_slice(len) });
+        let mut guard = ManuallyDrop::new(guard);
+        unsafe {
+            ptr::write(guard.data.get_label_mut(), label);
+            Self::from_base(ptr::read(&guard.data), len)
+        }
+    }
+}
+
+/// Cleans up the array of `with_elements_then_label` if a function panics
+/// before its label is written.
+struct UnlabelledGuard<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    data: BaseArray<E, L, P>,
+    len: usize,
+    initialized: usize,
+}
+
+impl<E, L, P> Drop for UnlabelledGuard<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            for i in 0..self.initialized {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(self.len);
+        }
+    }
+}
+
+impl<E, P> SafeArray<E, (), P>
+where
+    P: SafeArrayPtr<E, ()>,
+{
+    /// Creates a new array of length `len`, where the element at index `i` is
+    /// `func(state, i)`. Useful for threading construction state, like a random
+    /// number generator, through the constructor without storing it in the label.
+    ///
+    /// If `func` panics, the elements that were already created are dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut seed: u32 = 7;
+    /// let array = HeapArray::new_stateful(&mut seed, 3, |seed, _| {
+    ///     *seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+    ///     *seed % 100
+    /// });
+    /// assert!(array.len() == 3);
+    /// assert!(seed != 7);
+    /// ```
+    pub fn new_stateful<S, F>(state: &mut S, len: usize, mut func: F) -> Self
+    where
+        F: FnMut(&mut S, usize) -> E,
+    {
+        Self::new(len, |i| func(state, i))
+    }
+
+    /// Creates a new array by calling `func(0)`, `func(1)`, and so on until
+    /// `func` returns `None`, keeping every value returned before that.
+    ///
+    /// Since the length isn't known ahead of time, the values are buffered in a
+    /// `Vec` and then moved into the array; `func` is only ever called once per
+    /// index.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let (mut a, mut b) = (0, 1);
+    /// let fib = HeapArray::from_fn_while(|_| {
+    ///     let out = a;
+    ///     a = b;
+    ///     b += out;
+    ///     if out < 100 { Some(out) } else { None }
+    /// });
+    /// assert!(fib.as_slice() == &[0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89]);
+    /// ```
+    pub fn from_fn_while<F>(mut func: F) -> Self
+    where
+        F: FnMut(usize) -> Option<E>,
+    {
+        let mut buffer = Vec::new();
+        while let Some(elem) = func(buffer.len()) {
+            buffer.push(elem);
+        }
+        let len = buffer.len();
+        let mut elements = buffer.into_iter();
+        Self::new(len, |_| elements.next().unwrap())
+    }
+
+    /// Creates a new array of length `2 * half_len`, whose first half is
+    /// initialized using `func`, and whose second half mirrors the first; i.e.
+    /// the element at `2 * half_len - 1 - i` is a clone of the element at `i`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::from_mirrored(2, |i| i + 1);
+    /// assert!(array.as_slice() == &[1, 2, 2, 1]);
+    /// ```
+    pub fn from_mirrored<F>(half_len: usize, mut func: F) -> Self
+    where
+        F: FnMut(usize) -> E,
+        E: Clone,
+    {
+        let len = half_len
+            .checked_mul(2)
+            .expect("Length of mirrored array overflows `usize`");
+        let mut data = unsafe { BaseArray::new_lazy((), len) };
+        for i in 0..half_len {
+            unsafe { ptr::write(data.get_ptr_mut(i), func(i)) };
+        }
+        for i in half_len..len {
+            let elem = unsafe { data.get(len - 1 - i).clone() };
+            unsafe { ptr::write(data.get_ptr_mut(i), elem) };
+        }
+        unsafe { Self::from_base(data, len) }
+    }
+
+    /// Creates a new array of length `len` from a collection of `(index, value)`
+    /// pairs, like a `BTreeMap<usize, E>` or `HashMap<usize, E>`. Indices that
+    /// don't appear in `entries` are set to `E::default()`.
+    ///
+    /// # Panics
+    /// Panics if any index in `entries` is out of bounds.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::from_index_map(4, vec![(1, 10), (3, 30)]);
+    /// assert!(array.as_slice() == &[0, 10, 0, 30]);
+    /// ```
+    pub fn from_index_map<M>(len: usize, entries: M) -> Self
+    where
+        M: IntoIterator<Item = (usize, E)>,
+        E: Default,
+    {
+        let mut array = Self::new(len, |_| E::default());
+        for (idx, value) in entries {
+            assert!(
+                idx < len,
+                "Index {} is out of bounds for an array of length {}",
+                idx,
+                len
+            );
+            array[idx] = value;
+        }
+        array
+    }
+
+    /// Creates a new array by interleaving the elements of `parts` round-robin,
+    /// so that the element at index `i` of the new array is taken from the array
+    /// at index `i % N`. Elements are moved, not cloned. This is the inverse of
+    /// [`scatter`](#method.scatter).
+    ///
+    /// # Panics
+    /// Panics if `N == 0`, or if the lengths of `parts` aren't what `scatter`
+    /// would produce; i.e. the first `len % N` parts must have one more element
+    /// than the rest.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let evens = HeapArray::new(3, |i| i * 2);
+    /// let odds = HeapArray::new(2, |i| i * 2 + 1);
+    /// let array = HeapArray::gather([evens, odds]);
+    /// assert!(array.as_slice() == &[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn gather<const N: usize>(parts: [Self; N]) -> Self {
+        assert!(N != 0, "Cannot gather an array from 0 arrays");
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        for (idx, part) in parts.iter().enumerate() {
+            let expected = len / N + if idx < len % N { 1 } else { 0 };
+            assert!(
+                part.len() == expected,
+                "Part {} has length {}, but should have length {}",
+                idx,
+                part.len(),
+                expected
+            );
+        }
+        let mut parts = parts.map(|part| {
+            let part_len = part.len();
+            (part.into_base(), part_len)
+        });
+        let array = Self::new(len, |i| unsafe { ptr::read(parts[i % N].0.get_ptr(i / N)) });
+        for (data, part_len) in &mut parts {
+            unsafe { data.drop_lazy(*part_len) };
+        }
+        array
+    }
+
+    /// Creates a new array of length `1 << len_log2`, with elements initialized
+    /// using `func`.
+    ///
+    /// # Panics
+    /// Panics if `1 << len_log2` is larger than `max_len()`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new_pow2(3, |i| i);
+    /// assert!(array.len() == 8);
+    /// assert!(array.is_pow2_len());
+    /// ```
+    pub fn new_pow2<F>(len_log2: u32, func: F) -> Self
+    where
+        F: FnMut(usize) -> E,
+    {
+        let len = 1usize
+            .checked_shl(len_log2)
+            .filter(|&len| len <= Self::max_len())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Length 2^{} is larger than the maximum length {}",
+                    len_log2,
+                    Self::max_len()
+                )
+            });
+        Self::new(len, func)
+    }
+
+    /// Creates a new array of length `len` with elements initialized using
+    /// `func`, and then sorted in ascending order.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new_sorted(10, |i| (i * 7919) % 13);
+    /// assert!(array.as_slice().windows(2).all(|pair| pair[0] <= pair[1]));
+    /// ```
+    pub fn new_sorted<F>(len: usize, func: F) -> Self
+    where
+        F: FnMut(usize) -> E,
+        E: Ord,
+    {
+        let mut array = Self::new(len, func);
+        array.as_slice_mut().sort();
+        array
+    }
+}
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    L: Clone,
+{
+    /// Creates a new array whose label is a clone of the label of `other`, and
+    /// whose elements are initialized using `func`.
+    ///
+    /// The two labels are independent of each other after construction.
+    pub fn with_shared_label<E2, P2, F>(
+        other: &SafeArray<E2, L, P2>,
+        len: usize,
+        mut func: F,
+    ) -> Self
+    where
+        P2: SafeArrayPtr<E2, L>,
+        F: FnMut(usize) -> E,
+    {
+        Self::with_label(other.get_label().clone(), len, |_, idx| func(idx))
+    }
+}
+
+impl<E> FatPtrArray<E, ()> {
+    /// Returns whether a `MemBlock<E, ()>` of length `len` has the same layout
+    /// as a heap-allocated buffer of `len` elements of type `E`, i.e. whether an
+    /// array of length `len` can share its allocation with a `Vec` or `Box<[E]>`.
+    pub(super) fn shares_slice_layout(len: usize) -> bool {
+        let elem_size = mem::size_of::<E>();
+        let slice_layout = (len * elem_size, mem::align_of::<E>());
+        elem_size != 0 && len != 0 && MemBlock::<E, ()>::memory_layout(len) == slice_layout
+    }
+
+    /// Creates a new array out of the elements of `vec`, reusing its allocation
+    /// when possible.
+    ///
+    /// The allocation of `vec` is adopted without copying when all of the
+    /// following are true:
+    ///
+    /// - `E` isn't zero-sized
+    /// - `vec` isn't empty
+    /// - `vec.len() == vec.capacity()`
+    /// - the memory layout of a `MemBlock<E, ()>` of length `vec.len()` is the
+    ///   same as the layout of the buffer of `vec`; this is always true for the
+    ///   current implementation of `MemBlock`, since the label `()` takes up no
+    ///   space
+    ///
+    /// Otherwise, a new array is allocated and the elements of `vec` are copied
+    /// into it in bulk. Calling `vec.shrink_to_fit()` beforehand makes reuse
+    /// more likely.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let vec = vec![1, 2, 3];
+    /// let ptr = vec.as_ptr();
+    /// let array = HeapArray::from_vec_reuse(vec);
+    /// assert!(array.as_slice() == &[1, 2, 3]);
+    /// assert!(array.as_slice().as_ptr() == ptr);
+    /// ```
+    pub fn from_vec_reuse(vec: Vec<E>) -> Self {
+        let len = vec.len();
+        let mut vec = ManuallyDrop::new(vec);
+        if len == vec.capacity() && Self::shares_slice_layout(len) {
+            unsafe {
+                let ptr = FatArrayPtr::from_ptr(vec.as_mut_ptr() as *mut u8);
+                Self::from_base(BaseArray::from_ptr(ptr), len)
+            }
+        } else {
+            unsafe {
+                let mut data = BaseArray::new_lazy((), len);
+                ptr::copy_nonoverlapping(vec.as_ptr(), data.get_ptr_mut(0), len);
+                vec.set_len(0);
+                ManuallyDrop::drop(&mut vec);
+                Self::from_base(data, len)
+            }
+        }
+    }
+}
+
+/// Cleans up the two partially initialized arrays of `unzip_new` if the
+/// generator panics.
+struct UnzipGuard<A, B> {
+    first: BaseArray<A, (), FatArrayPtr<A, ()>>,
+    second: BaseArray<B, (), FatArrayPtr<B, ()>>,
+    len: usize,
+    initialized: usize,
+}
+
+impl<A, B> Drop for UnzipGuard<A, B> {
+    fn drop(&mut self) {
+        unsafe {
+            for i in 0..self.initialized {
+                ptr::drop_in_place(self.first.get_ptr_mut(i));
+                ptr::drop_in_place(self.second.get_ptr_mut(i));
+            }
+            self.first.drop_lazy(self.len);
+            self.second.drop_lazy(self.len);
+        }
+    }
+}
+
+impl<A> FatPtrArray<A, ()> {
+    /// Creates two arrays of length `len` at once, where the elements at index
+    /// `i` are the two halves of `func(i)`. Both arrays are filled in the same
+    /// loop, without building an intermediate array of pairs.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let (nums, squares) = HeapArray::unzip_new(4, |i| (i, i * i));
+    /// assert!(nums.as_slice() == &[0, 1, 2, 3]);
+    /// assert!(squares.as_slice() == &[0, 1, 4, 9]);
+    /// ```
+    pub fn unzip_new<B, F>(len: usize, mut func: F) -> (Self, FatPtrArray<B, ()>)
+    where
+        F: FnMut(usize) -> (A, B),
+    {
+        let mut guard = UnzipGuard {
+            first: unsafe { BaseArray::new_lazy((), len) },
+            second: unsafe { BaseArray::new_lazy((), len) },
+            len,
+            initialized: 0,
+        };
+        for i in 0..len {
+            let (a, b) = func(i);
+            unsafe {
+                ptr::write(guard.first.get_ptr_mut(i), a);
+                ptr::write(guard.second.get_ptr_mut(i), b);
+            }
+            guard.initialized += 1;
+        }
+        let guard = ManuallyDrop::new(guard);
+        unsafe {
+            (
+                Self::from_base(ptr::read(&guard.first), len),
+                FatPtrArray::from_base(ptr::read(&guard.second), len),
+            )
+        }
+    }
+}
+
+/// Adopts the allocation of the boxed slice if its layout matches the layout of
+/// the array, which is true whenever `E` isn't zero-sized and the slice isn't
+/// empty. Otherwise the elements are copied into a new allocation in bulk.
+///
+/// ```rust
+/// # use heaparray::*;
+/// let boxed: Box<[u32]> = Box::new([1, 2, 3]);
+/// let array = HeapArray::from(boxed);
+/// assert!(array.as_slice() == &[1, 2, 3]);
+/// ```
+impl<E> From<Box<[E]>> for FatPtrArray<E, ()> {
+    fn from(boxed: Box<[E]>) -> Self {
+        Self::from_vec_reuse(boxed.into_vec())
+    }
+}
+
+/// Moves the elements of the `Vec` into an array; see
+/// `FatPtrArray::from_vec_reuse` for when its allocation is reused. Otherwise
+/// the elements are moved into a new allocation in bulk, and the buffer of the
+/// `Vec` is freed without dropping them.
+///
+/// ```rust
+/// # use heaparray::*;
+/// let array = HeapArray::from(vec![1, 2, 3]);
+/// assert!(array.as_slice() == &[1, 2, 3]);
+/// ```
+impl<E> From<Vec<E>> for FatPtrArray<E, ()> {
+    fn from(vec: Vec<E>) -> Self {
+        Self::from_vec_reuse(vec)
+    }
+}
@@ -0,0 +1,67 @@
+//! Contains helpers shared by the `Debug` implementations of the arrays in this
+//! crate.
+
+use crate::prelude::*;
+
+/// Number of elements printed when the formatter doesn't specify a precision.
+const DEFAULT_LIMIT: usize = 32;
+
+/// Most elements that are printed from the end of an elided array.
+const TAIL_LEN: usize = 3;
+
+/// Formats a slice of elements as a list, printing at most `limit` elements;
+/// the rest are replaced with `...`.
+///
+/// The limit is the precision of the formatter, so `{:.5?}` prints at most 5
+/// elements, and is `DEFAULT_LIMIT` otherwise. The precision isn't passed on to
+/// the elements themselves.
+pub(crate) struct ElidedSlice<'a, E>(pub(crate) &'a [E]);
+
+impl<'a, E> fmt::Debug for ElidedSlice<'a, E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let limit = formatter.precision().unwrap_or(DEFAULT_LIMIT);
+        let elements = self.0;
+        let mut list = formatter.debug_list();
+        if elements.len() <= limit {
+            list.entries(elements.iter().map(NoPrecision));
+        } else {
+            let tail = core::cmp::min(TAIL_LEN, limit / 2);
+            let head = limit - tail;
+            list.entries(elements[..head].iter().map(NoPrecision));
+            list.entry(&Ellipsis);
+            list.entries(elements[(elements.len() - tail)..].iter().map(NoPrecision));
+        }
+        list.finish()
+    }
+}
+
+/// Formats a value with `Debug`, ignoring the precision of the formatter.
+///
+/// Used for the parts of an array that aren't elided, since the precision given
+/// to an array's `Debug` implementation is the number of elements to print.
+pub(crate) struct NoPrecision<'a, T>(pub(crate) &'a T);
+
+impl<'a, T> fmt::Debug for NoPrecision<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if formatter.alternate() {
+            write!(formatter, "{:#?}", self.0)
+        } else {
+            write!(formatter, "{:?}", self.0)
+        }
+    }
+}
+
+/// Placeholder for elements that were left out.
+struct Ellipsis;
+
+impl fmt::Debug for Ellipsis {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("...")
+    }
+}
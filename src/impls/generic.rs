@@ -1,5 +1,12 @@
+use super::fmt_utils::{ElidedSlice, NoPrecision};
+use super::labelled_slice::LabelledSlice;
 use crate::base::*;
 use crate::prelude::*;
+use core::alloc::Layout;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::{RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
 
 /// Array pointer that also knows what its length is.
 ///
@@ -22,6 +29,24 @@ pub unsafe trait SafeArrayPtr<E, L>: BaseArrayPtr<E, L> {
     fn get_len(&self) -> usize;
 }
 
+/// Reason that an element couldn't be inserted into an array by `try_insert`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InsertError {
+    /// The index was out of bounds.
+    OutOfBounds,
+    /// The data of a reference-counted array is shared, so it can't be mutated.
+    Shared,
+}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertError::OutOfBounds => formatter.write_str("Index is out of bounds"),
+            InsertError::Shared => formatter.write_str("Array data is shared"),
+        }
+    }
+}
+
 /// Safe, generic interface to [`BaseArray`](../base/struct.BaseArray.html).
 ///
 /// Uses length information to guarrantee memory safety, and excludes operations
@@ -34,6 +59,456 @@ where
     data: BaseArray<E, L, P>,
 }
 
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Wraps a `BaseArray` whose label and first `len` elements have already been
+    /// initialized.
+    pub(crate) unsafe fn from_base(data: BaseArray<E, L, P>, len: usize) -> Self {
+        let mut out = Self { data };
+        out.data.as_ptr_mut().set_len(len);
+        out
+    }
+
+    /// Returns the underlying `BaseArray` of this array without running any
+    /// destructors.
+    pub(crate) fn into_base(self) -> BaseArray<E, L, P> {
+        let data = unsafe { core::ptr::read(&self.data) };
+        mem::forget(self);
+        data
+    }
+
+    /// Creates a new array, with values initialized using a provided function,
+    /// and label initialized to a provided value. Returns an error instead of
+    /// panicking if the array couldn't be allocated.
+    ///
+    /// If `func` panics, the label and the elements initialized so far are
+    /// dropped, and the array is deallocated.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// use heaparray::base::TryReserveError;
+    ///
+    /// let array = HeapArray::try_with_label((), 10, |_, i| i).unwrap();
+    /// assert!(array.len() == 10);
+    ///
+    /// let len = HeapArray::<u64, ()>::max_len() + 1;
+    /// match HeapArray::<u64, ()>::try_with_label((), len, |_, _| 0) {
+    ///     Err(TryReserveError::LengthOverflow { .. }) => {}
+    ///     _ => panic!("should have failed"),
+    /// }
+    /// ```
+    pub fn try_with_label<F>(label: L, len: usize, func: F) -> Result<Self, TryReserveError>
+    where
+        F: FnMut(&mut L, usize) -> E,
+    {
+        let data = BaseArray::try_new(label, len, func)?;
+        Ok(unsafe { Self::from_base(data, len) })
+    }
+
+    /// Returns the maximum length of an array of this type.
+    ///
+    /// Trying to create an array longer than this will panic.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// println!("{}", HeapArray::<u64, ()>::max_len());
+    /// ```
+    pub fn max_len() -> usize {
+        P::max_len()
+    }
+
+    /// Returns whether the length of this array is a power of two.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// assert!(HeapArray::new(4, |i| i).is_pow2_len());
+    /// assert!(!HeapArray::new(6, |i| i).is_pow2_len());
+    /// ```
+    pub fn is_pow2_len(&self) -> bool {
+        self.len().is_power_of_two()
+    }
+
+    /// Returns references to the elements at each of the given indices, in the
+    /// order they were given, or `None` if any of the indices are out of bounds.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| i * 10);
+    /// assert!(array.get_all(&[2, 0, 2]) == Some(vec![&20, &0, &20]));
+    /// assert!(array.get_all(&[0, 3]) == None);
+    /// ```
+    pub fn get_all(&self, indices: &[usize]) -> Option<Vec<&E>> {
+        indices.iter().map(|&idx| self.get(idx)).collect()
+    }
+
+    /// Returns mutable references to the elements at several indices at once,
+    /// or `None` if any of the indices are out of bounds, or if any two of them
+    /// are equal.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::new(4, |i| i);
+    /// let [a, b] = array.get_many_mut([0, 3]).unwrap();
+    /// core::mem::swap(a, b);
+    /// assert!(array.as_slice() == &[3, 1, 2, 0]);
+    /// assert!(array.get_many_mut([1, 1]).is_none());
+    /// assert!(array.get_many_mut([1, 4]).is_none());
+    /// ```
+    pub fn get_many_mut<const N: usize>(&mut self, indices: [usize; N]) -> Option<[&mut E; N]> {
+        let slice = self.as_slice_mut();
+        for (i, &idx) in indices.iter().enumerate() {
+            if idx >= slice.len() || indices[..i].contains(&idx) {
+                return None;
+            }
+        }
+        let ptr = slice.as_mut_ptr();
+        // The indices are in bounds of the slice and distinct, so the references
+        // don't alias.
+        Some(indices.map(|idx| unsafe { &mut *ptr.add(idx) }))
+    }
+
+    /// Returns a raw pointer to the first element of this array.
+    ///
+    /// The pointer is aligned and valid for reads of `self.len()` elements, even
+    /// if the array is empty. It's invalidated when the array is dropped.
+    pub fn as_ptr(&self) -> *const E {
+        self.data.get_ptr(0)
+    }
+
+    /// Returns a mutable raw pointer to the first element of this array.
+    ///
+    /// The pointer is aligned and valid for reads and writes of `self.len()`
+    /// elements, even if the array is empty. It's invalidated when the array is
+    /// dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::new(3, |i| i);
+    /// unsafe { *array.as_mut_ptr().add(1) = 10 };
+    /// assert!(array.as_slice() == &[0, 10, 2]);
+    /// ```
+    pub fn as_mut_ptr(&mut self) -> *mut E {
+        self.data.get_ptr_mut(0)
+    }
+
+    /// Replaces the element at index `key` with `value`, returning the old
+    /// element. Unlike `insert`, gives `value` back along with the reason it
+    /// couldn't be inserted on failure.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// # use heaparray::impls::InsertError;
+    /// let mut array = HeapArray::new(3, |i| i);
+    /// assert!(array.try_insert(1, 10) == Ok(1));
+    /// assert!(array.try_insert(3, 10) == Err((InsertError::OutOfBounds, 10)));
+    /// ```
+    pub fn try_insert(&mut self, key: usize, value: E) -> Result<E, (InsertError, E)> {
+        match self.get_mut(key) {
+            Some(slot) => Ok(mem::replace(slot, value)),
+            None => Err((InsertError::OutOfBounds, value)),
+        }
+    }
+
+    /// Consumes this array, returning a pointer to its memory block and its
+    /// length, without running any destructors. Like `Box::into_raw`, the array
+    /// can be rebuilt later by passing both values to `SafeArray::from_raw`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::with_label("label", 3, |_, i| i);
+    /// let (ptr, len) = array.into_raw();
+    /// let array = unsafe { HeapArray::<usize, &str>::from_raw(ptr, len) };
+    /// assert!(*array.get_label() == "label");
+    /// assert!(array.as_slice() == &[0, 1, 2]);
+    /// ```
+    pub fn into_raw(self) -> (*mut u8, usize) {
+        let len = self.len();
+        (self.into_base().as_ptr().as_ptr(), len)
+    }
+
+    /// Rebuilds an array from the pointer and length returned by
+    /// `SafeArray::into_raw`.
+    ///
+    /// # Safety
+    /// `ptr` and `len` must have been returned by `into_raw` on an array with
+    /// exactly the same element type `E`, label type `L`, and pointer type `P`,
+    /// and the array can only be rebuilt once.
+    pub unsafe fn from_raw(ptr: *mut u8, len: usize) -> Self {
+        Self::from_base(BaseArray::from_ptr(P::from_ptr(ptr)), len)
+    }
+
+    /// Consumes this array and leaks its memory block, returning a mutable
+    /// reference to its elements that lives for the rest of the program. Like
+    /// `Box::leak`, the label and elements are never dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let table: &'static mut [u32] = HeapArray::new(4, |i| (i * i) as u32).leak();
+    /// table[0] = 10;
+    /// assert!(table == &[10, 1, 4, 9]);
+    /// ```
+    pub fn leak(self) -> &'static mut [E]
+    where
+        E: 'static,
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe { core::slice::from_raw_parts_mut(data.get_ptr_mut(0), len) }
+    }
+
+    /// Returns the alignment, in bytes, that the elements of this array are
+    /// guaranteed to have.
+    ///
+    /// This is at least `align_of::<E>()`, but may be larger if the label forces
+    /// a larger alignment. Empty arrays might not have allocated elements, so
+    /// the alignment isn't meaningful for them.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(4, |i| i as u32);
+    /// assert!(array.alignment() >= core::mem::align_of::<u32>());
+    /// assert!(array.as_ptr() as usize % array.alignment() == 0);
+    /// ```
+    pub fn alignment(&self) -> usize {
+        P::elements_align()
+    }
+
+    /// Returns the offset, in bytes, of the first element of an array of this
+    /// type from the start of its memory block. It only depends on the types of
+    /// the label and elements, so it doesn't need an instance of the array.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// assert!(HeapArray::<u32, u8>::elements_offset() == 4);
+    /// assert!(impls::ThinPtrArray::<u8, ()>::elements_offset() == core::mem::size_of::<usize>());
+    /// ```
+    pub fn elements_offset() -> usize {
+        P::elements_offset()
+    }
+
+    /// Returns the size and alignment of the memory block that holds the label
+    /// and elements of this array.
+    ///
+    /// Blocks with a size of 0 aren't actually allocated.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::with_label(0u8, 3, |_, i| i as u32);
+    /// let layout = array.block_layout();
+    /// assert!(layout.size() == 16 && layout.align() == 4);
+    /// ```
+    pub fn block_layout(&self) -> Layout {
+        let (size, align) = P::memory_layout(self.len());
+        // The block was created with this layout, so it's already been checked.
+        unsafe { Layout::from_size_align_unchecked(size, align) }
+    }
+
+    /// Clones this array by copying all of its elements at once, instead of
+    /// cloning them one at a time like `Clone::clone` does.
+    ///
+    /// `Clone::clone` can't do this itself for `Copy` elements without
+    /// specialization, which isn't available on stable Rust, so the bulk copy
+    /// only happens when this method is called directly.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(1000, |i| i as u64);
+    /// assert!(array.clone_copy() == array);
+    /// ```
+    pub fn clone_copy(&self) -> Self
+    where
+        E: Copy,
+        L: Clone,
+    {
+        let len = self.len();
+        unsafe { Self::from_base(self.data.clone_copy(len), len) }
+    }
+
+    /// Returns a borrowed view into the label and elements of this array.
+    pub fn as_labelled_slice(&self) -> LabelledSlice<'_, E, L> {
+        LabelledSlice::new(self.get_label(), self.as_slice())
+    }
+
+    /// Returns an iterator that consumes this array, yielding its elements from
+    /// last to first.
+    ///
+    /// Any elements that haven't been yielded when the iterator is dropped are
+    /// dropped along with the label.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(4, |i| i);
+    /// let reversed: Vec<_> = array.into_iter_rev().collect();
+    /// assert!(reversed == vec![3, 2, 1, 0]);
+    /// ```
+    pub fn into_iter_rev(self) -> impl ExactSizeIterator<Item = E> {
+        let remaining = self.len();
+        let data = self.into_base();
+        SafeArrayRevIter { data, remaining }
+    }
+
+    /// Shortens this array to `new_len` elements, dropping the rest and
+    /// reallocating the array to its new length. Does nothing if `new_len` is
+    /// at least the length of this array.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::new(5, |i| i);
+    /// array.truncate(2);
+    /// assert!(array.as_slice() == &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, new_len: usize) {
+        let len = self.len();
+        if new_len >= len {
+            return;
+        }
+        // Like `Vec::truncate`, the length is set before the tail is dropped,
+        // so a panicking destructor can't make this array drop it again.
+        unsafe {
+            self.data.as_ptr_mut().set_len(new_len);
+            let guard = ShrinkGuard {
+                array: &mut self.data,
+                len,
+                new_len,
+            };
+            let tail = guard.array.get_ptr_mut(new_len);
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(tail, len - new_len));
+        }
+    }
+
+    /// Returns an iterator over references to the elements of this array, each
+    /// paired with a reference to the label.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u32, u32>::with_label(10, 3, |_, i| i as u32);
+    /// let scaled: Vec<_> = array.iter_with_label().map(|(scale, elem)| scale * elem).collect();
+    /// assert!(scaled == vec![0, 10, 20]);
+    /// ```
+    pub fn iter_with_label(&self) -> impl ExactSizeIterator<Item = (&L, &E)> {
+        let label = self.get_label();
+        self.iter().map(move |elem| (label, elem))
+    }
+
+    /// Returns an iterator over mutable references to the elements of this
+    /// array, along with their indices.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::new(5, |i| i);
+    /// for (i, elem) in array.iter_mut_indexed() {
+    ///     if i % 2 == 0 {
+    ///         *elem *= 2;
+    ///     }
+    /// }
+    /// assert!(array.as_slice() == &[0, 1, 4, 3, 8]);
+    /// ```
+    pub fn iter_mut_indexed(&mut self) -> impl ExactSizeIterator<Item = (usize, &mut E)> {
+        self.iter_mut().enumerate()
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Hash + Eq + Clone,
+{
+    /// Returns the number of times each element occurs in this array. Each
+    /// distinct element is cloned once, to be used as a key in the map.
+    ///
+    /// Not available with the `no-std` feature.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| ["a", "a", "b"][i]);
+    /// let counts = array.frequencies();
+    /// assert!(counts.len() == 2);
+    /// assert!(counts["a"] == 2);
+    /// assert!(counts["b"] == 1);
+    /// ```
+    pub fn frequencies(&self) -> std::collections::HashMap<E, usize> {
+        let mut counts = std::collections::HashMap::new();
+        for elem in self.iter() {
+            match counts.get_mut(elem) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(elem.clone(), 1);
+                }
+            }
+        }
+        counts
+    }
+}
+
+/// Shrinks the block of an array to its new length once the elements past it
+/// have been dropped, even if one of their destructors panics.
+struct ShrinkGuard<'a, E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    array: &'a mut BaseArray<E, L, P>,
+    len: usize,
+    new_len: usize,
+}
+
+impl<'a, E, L, P> Drop for ShrinkGuard<'a, E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe { self.array.realloc(self.len, self.new_len) }
+    }
+}
+
+/// Iterator that takes ownership of a `SafeArray` and yields its elements in
+/// reverse order.
+struct SafeArrayRevIter<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    data: BaseArray<E, L, P>,
+    remaining: usize,
+}
+
+impl<E, L, P> Iterator for SafeArrayRevIter<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    type Item = E;
+    fn next(&mut self) -> Option<E> {
+        if self.remaining == 0 {
+            None
+        } else {
+            self.remaining -= 1;
+            Some(unsafe { core::ptr::read(self.data.get_ptr(self.remaining)) })
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<E, L, P> ExactSizeIterator for SafeArrayRevIter<E, L, P> where P: SafeArrayPtr<E, L> {}
+
+impl<E, L, P> Drop for SafeArrayRevIter<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        let len = self.data.as_ptr().get_len();
+        unsafe {
+            core::ptr::drop_in_place(self.data.get_label_mut());
+            for i in 0..self.remaining {
+                core::ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(len);
+        }
+    }
+}
+
 impl<E, L, P> Container for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -72,10 +547,7 @@ where
         }
     }
     fn insert(&mut self, key: usize, value: E) -> Option<E> {
-        match self.get_mut(key) {
-            Some(slot) => Some(mem::replace(slot, value)),
-            None => None,
-        }
+        self.try_insert(key, value).ok()
     }
 }
 
@@ -99,6 +571,21 @@ where
     unsafe fn get_unchecked(&self, idx: usize) -> &E {
         self.data.get(idx)
     }
+    unsafe fn _drop_contents<F>(&mut self, drop_label: F) -> bool
+    where
+        F: FnOnce(&mut L),
+    {
+        let len = self.len();
+        drop_label(self.data.get_label_mut());
+        for i in 0..len {
+            core::ptr::drop_in_place(self.data.get_ptr_mut(i));
+        }
+        true
+    }
+    unsafe fn _dealloc(&mut self) {
+        let len = self.len();
+        self.data.drop_lazy(len);
+    }
 }
 
 impl<E, L, P> LabelledArrayMut<E, L> for SafeArray<E, L, P>
@@ -125,6 +612,57 @@ where
     }
 }
 
+impl<E, P> core::iter::FromIterator<E> for SafeArray<E, (), P>
+where
+    P: SafeArrayPtr<E, ()>,
+{
+    /// Creates an array from the elements of an iterator.
+    ///
+    /// If the iterator's `size_hint` gives an exact length, the elements are
+    /// written directly into an array of that length, which is then resized if
+    /// the iterator turns out to yield a different number of elements. Otherwise
+    /// the elements are buffered in a `Vec` first.
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = E>,
+    {
+        let mut iter = iter.into_iter();
+        let len = match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => lower,
+            _ => {
+                let buffer: Vec<E> = iter.collect();
+                let len = buffer.len();
+                let mut elements = buffer.into_iter();
+                return Self::new(len, |_| elements.next().unwrap());
+            }
+        };
+
+        let mut data = unsafe { BaseArray::new_lazy((), len) };
+        let mut written = 0;
+        while written < len {
+            match iter.next() {
+                Some(elem) => unsafe { core::ptr::write(data.get_ptr_mut(written), elem) },
+                None => break,
+            }
+            written += 1;
+        }
+
+        if written < len {
+            unsafe { data.realloc(len, written) };
+        } else {
+            let rest: Vec<E> = iter.collect();
+            if !rest.is_empty() {
+                unsafe { data.realloc(len, len + rest.len()) };
+                for elem in rest {
+                    unsafe { core::ptr::write(data.get_ptr_mut(written), elem) };
+                    written += 1;
+                }
+            }
+        }
+        unsafe { Self::from_base(data, written) }
+    }
+}
+
 impl<E, L, P> DefaultLabelledArray<E, L> for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -135,6 +673,18 @@ where
     }
 }
 
+/// Creates an empty array with a default label.
+impl<E, L, P> Default for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    L: Default,
+{
+    fn default() -> Self {
+        Self::empty_with_label(L::default())
+    }
+}
+
+// Clones elements one at a time even when they're `Copy`; see `clone_copy`.
 impl<E, L, P> Clone for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -210,25 +760,127 @@ where
     }
 }
 
-impl<E, L, P> Index<Range<usize>> for SafeArray<E, L, P>
+impl<E, L, P> AsRef<[E]> for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    fn as_ref(&self) -> &[E] {
+        self.as_slice()
+    }
+}
+
+impl<E, L, P> AsMut<[E]> for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
 {
-    type Output = [E];
-    fn index(&self, idx: Range<usize>) -> &[E] {
-        &self.as_slice()[idx]
+    fn as_mut(&mut self) -> &mut [E] {
+        self.as_slice_mut()
+    }
+}
+
+/// Only implemented for arrays without a label, since `Hash`, `Eq`, and `Ord`
+/// for arrays take the label into account, and those have to agree with the
+/// implementations for `[E]` for `Borrow` to be used correctly, e.g. to look up
+/// arrays in a `HashMap` by slice. A label of `()` hashes and compares the same
+/// as no label.
+impl<E, P> Borrow<[E]> for SafeArray<E, (), P>
+where
+    P: SafeArrayPtr<E, ()>,
+{
+    fn borrow(&self) -> &[E] {
+        self.as_slice()
+    }
+}
+
+/// Only implemented for arrays without a label; see the implementation of
+/// `Borrow`.
+impl<E, P> BorrowMut<[E]> for SafeArray<E, (), P>
+where
+    P: SafeArrayPtr<E, ()>,
+{
+    fn borrow_mut(&mut self) -> &mut [E] {
+        self.as_slice_mut()
     }
 }
 
-impl<E, L, P> IndexMut<Range<usize>> for SafeArray<E, L, P>
+impl<E, L, P> MutSliceOps<E> for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
 {
-    fn index_mut(&mut self, idx: Range<usize>) -> &mut [E] {
-        &mut self.as_slice_mut()[idx]
+    fn fill(&mut self, value: E)
+    where
+        E: Clone,
+    {
+        self.as_slice_mut().fill(value)
+    }
+
+    fn fill_with<F>(&mut self, func: F)
+    where
+        F: FnMut() -> E,
+    {
+        self.as_slice_mut().fill_with(func)
     }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.as_slice_mut().swap(i, j)
+    }
+
+    fn sort(&mut self)
+    where
+        E: Ord,
+    {
+        self.as_slice_mut().sort()
+    }
+
+    fn sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&E, &E) -> Ordering,
+    {
+        self.as_slice_mut().sort_by(compare)
+    }
+
+    fn sort_unstable(&mut self)
+    where
+        E: Ord,
+    {
+        self.as_slice_mut().sort_unstable()
+    }
+}
+
+macro_rules! impl_range_index {
+    ($($range:ty),*) => {
+        $(
+            impl<E, L, P> Index<$range> for SafeArray<E, L, P>
+            where
+                P: SafeArrayPtr<E, L>,
+            {
+                type Output = [E];
+                fn index(&self, idx: $range) -> &[E] {
+                    &self.as_slice()[idx]
+                }
+            }
+
+            impl<E, L, P> IndexMut<$range> for SafeArray<E, L, P>
+            where
+                P: SafeArrayPtr<E, L>,
+            {
+                fn index_mut(&mut self, idx: $range) -> &mut [E] {
+                    &mut self.as_slice_mut()[idx]
+                }
+            }
+        )*
+    };
 }
 
+impl_range_index!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>
+);
+
 impl<'a, E, L, P> IntoIterator for &'a SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -259,7 +911,7 @@ where
     L: PartialEq<L2>,
 {
     fn eq(&self, other: &SafeArray<E2, L2, P2>) -> bool {
-        if self.get_label().eq(other.get_label()) {
+        if self.len() == other.len() && self.get_label().eq(other.get_label()) {
             for (e1, e2) in self.into_iter().zip(other.into_iter()) {
                 if e1.ne(e2) {
                     return false;
@@ -272,6 +924,31 @@ where
     }
 }
 
+// Compares only the elements of the array with the elements of a slice, since
+// slices don't have a label.
+macro_rules! impl_slice_eq {
+    ($([$($params:tt)*] $rhs:ty),*) => {
+        $(
+            impl<$($params)* E, L, P, E2> PartialEq<$rhs> for SafeArray<E, L, P>
+            where
+                P: SafeArrayPtr<E, L>,
+                E: PartialEq<E2>,
+            {
+                fn eq(&self, other: &$rhs) -> bool {
+                    self.as_slice() == &other[..]
+                }
+            }
+        )*
+    };
+}
+
+impl_slice_eq!(
+    [] [E2],
+    ['a,] &'a [E2],
+    [] Vec<E2>,
+    [const N: usize,] [E2; N]
+);
+
 impl<E, L, P> Eq for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -280,6 +957,59 @@ where
 {
 }
 
+/// Compares the labels of the two arrays first, and if they're equal, compares
+/// their elements lexicographically. This is consistent with `PartialEq`, which
+/// also takes the label into account.
+impl<E, L, P, E2, L2, P2> PartialOrd<SafeArray<E2, L2, P2>> for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    P2: SafeArrayPtr<E2, L2>,
+    E: PartialOrd<E2>,
+    L: PartialOrd<L2>,
+{
+    fn partial_cmp(&self, other: &SafeArray<E2, L2, P2>) -> Option<Ordering> {
+        match self.get_label().partial_cmp(other.get_label()) {
+            Some(Ordering::Equal) => {}
+            ordering => return ordering,
+        }
+        for (e1, e2) in self.as_slice().iter().zip(other.as_slice()) {
+            match e1.partial_cmp(e2) {
+                Some(Ordering::Equal) => {}
+                ordering => return ordering,
+            }
+        }
+        self.len().partial_cmp(&other.len())
+    }
+}
+
+/// Compares the labels of the two arrays first, and if they're equal, compares
+/// their elements lexicographically.
+impl<E, L, P> Ord for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Ord,
+    L: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get_label()
+            .cmp(other.get_label())
+            .then_with(|| self.as_slice().cmp(other.as_slice()))
+    }
+}
+
+/// Hashes the label, and then the elements in order.
+impl<E, L, P> Hash for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Hash,
+    L: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get_label().hash(state);
+        self.as_slice().hash(state);
+    }
+}
+
 impl<E, L, P> fmt::Debug for SafeArray<E, L, P>
 where
     P: SafeArrayPtr<E, L>,
@@ -289,9 +1019,9 @@ where
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter
             .debug_struct("Array")
-            .field("label", &self.get_label())
+            .field("label", &NoPrecision(self.get_label()))
             .field("len", &self.len())
-            .field("elements", &self.as_slice())
+            .field("elements", &ElidedSlice(self.as_slice()))
             .finish()
     }
 }
@@ -0,0 +1,281 @@
+//! Contains the definition for `GrowableArray`, an array on the heap that can
+//! change its length after construction.
+
+use super::fmt_utils::{ElidedSlice, NoPrecision};
+use super::p_types::FatArrayPtr;
+use crate::base::*;
+use crate::prelude::*;
+use core::ptr;
+
+/// Array on the heap that can grow and shrink, like a `Vec` that holds a label.
+///
+/// The label is stored once at the front of the block, and the elements follow
+/// it. Tracks its length and capacity separately from the block, and doubles
+/// its capacity whenever it runs out of room, so `push` is amortized O(1).
+///
+/// ```rust
+/// # use heaparray::impls::*;
+/// let mut array = GrowableArray::with_capacity(1);
+/// array.push(1);
+/// array.push(2);
+/// assert!(array.as_slice() == &[1, 2]);
+/// assert!(array.pop() == Some(2));
+/// assert!(array.len() == 1);
+/// ```
+pub struct GrowableArray<E, L = (), P = FatArrayPtr<E, L>>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    data: BaseArray<E, L, P>,
+    len: usize,
+    cap: usize,
+}
+
+impl<E, L, P> GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    /// Returns the number of elements this array can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Makes sure this array can hold at least `additional` more elements without
+    /// reallocating. Reserves more than that if it's needed to keep `push`
+    /// amortized O(1).
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `usize`, or is larger than the
+    /// maximum length of the underlying block.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self
+            .len
+            .checked_add(additional)
+            .expect("Capacity of GrowableArray overflows `usize`");
+        if needed <= self.cap {
+            return;
+        }
+        let doubled = core::cmp::min(self.cap.saturating_mul(2), P::max_len());
+        let new_cap = core::cmp::max(needed, doubled);
+        unsafe { self.data.realloc(self.cap, new_cap) };
+        self.cap = new_cap;
+    }
+
+    /// Appends `value` to the end of this array, reallocating if it's full.
+    pub fn push(&mut self, value: E) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        unsafe { ptr::write(self.data.get_ptr_mut(self.len), value) };
+        self.len += 1;
+    }
+
+    /// Appends a copy of every element of `other` to the end of this array,
+    /// reallocating at most once.
+    ///
+    /// ```rust
+    /// # use heaparray::impls::*;
+    /// let mut buffer = GrowableArray::with_capacity(2);
+    /// buffer.extend_from_slice(b"heap");
+    /// buffer.extend_from_slice(b"array");
+    /// assert!(buffer.as_slice() == b"heaparray");
+    /// ```
+    pub fn extend_from_slice(&mut self, other: &[E])
+    where
+        E: Copy,
+    {
+        self.reserve(other.len());
+        unsafe {
+            let dest = self.data.get_ptr_mut(self.len);
+            ptr::copy_nonoverlapping(other.as_ptr(), dest, other.len());
+        }
+        self.len += other.len();
+    }
+
+    /// Appends a clone of every element of `other` to the end of this array,
+    /// reallocating at most once. Use `extend_from_slice` instead if the
+    /// elements are `Copy`.
+    pub fn extend_from_slice_cloned(&mut self, other: &[E])
+    where
+        E: Clone,
+    {
+        self.reserve(other.len());
+        for elem in other {
+            // The length is updated after every element, so if `clone` panics
+            // the elements cloned so far are still dropped.
+            unsafe { ptr::write(self.data.get_ptr_mut(self.len), elem.clone()) };
+            self.len += 1;
+        }
+    }
+
+    /// Removes the last element of this array and returns it, or returns `None`
+    /// if the array is empty.
+    pub fn pop(&mut self) -> Option<E> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some(unsafe { ptr::read(self.data.get_ptr(self.len)) })
+        }
+    }
+
+    /// Removes the element at `idx` and returns it, replacing it with the last
+    /// element of this array. Doesn't preserve the order of the elements, but
+    /// is O(1).
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds.
+    ///
+    /// ```rust
+    /// # use heaparray::impls::*;
+    /// let mut array = GrowableArray::with_capacity(4);
+    /// array.extend(0..4);
+    /// assert!(array.swap_remove(1) == 1);
+    /// assert!(array.as_slice() == &[0, 3, 2]);
+    /// ```
+    pub fn swap_remove(&mut self, idx: usize) -> E {
+        assert!(
+            idx < self.len,
+            "swap_remove index (is {}) should be < len (is {})",
+            idx,
+            self.len
+        );
+        self.len -= 1;
+        unsafe {
+            let removed = ptr::read(self.data.get_ptr(idx));
+            ptr::copy(self.data.get_ptr(self.len), self.data.get_ptr_mut(idx), 1);
+            removed
+        }
+    }
+
+    /// Get a reference to the label of this array.
+    pub fn get_label(&self) -> &L {
+        self.data.get_label()
+    }
+
+    /// Get a mutable reference to the label of this array.
+    pub fn get_label_mut(&mut self) -> &mut L {
+        self.data.get_label_mut()
+    }
+
+    /// Returns the initialized elements of this array as a slice.
+    pub fn as_slice(&self) -> &[E] {
+        unsafe { self.data.as_slice(self.len) }
+    }
+
+    /// Returns the initialized elements of this array as a mutable slice.
+    pub fn as_slice_mut(&mut self) -> &mut [E] {
+        unsafe { self.data.as_slice_mut(self.len) }
+    }
+}
+
+impl<E, L> GrowableArray<E, L> {
+    /// Creates an empty array with the given label, that can hold `cap` elements
+    /// without reallocating.
+    pub fn with_label_capacity(label: L, cap: usize) -> Self {
+        Self {
+            data: unsafe { BaseArray::new_lazy(label, cap) },
+            len: 0,
+            cap,
+        }
+    }
+}
+
+impl<E> GrowableArray<E> {
+    /// Creates an empty array that can hold `cap` elements without reallocating.
+    pub fn with_capacity(cap: usize) -> Self {
+        Self::with_label_capacity((), cap)
+    }
+}
+
+impl<E, L, P> Container for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<E, L, P> Drop for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.data.get_label_mut());
+            for i in 0..self.len {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(self.cap);
+        }
+    }
+}
+
+impl<E, L, P> Extend<E> for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = E>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<E, L, P> Index<usize> for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    type Output = E;
+    fn index(&self, idx: usize) -> &E {
+        &self.as_slice()[idx]
+    }
+}
+
+impl<E, L, P> IndexMut<usize> for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn index_mut(&mut self, idx: usize) -> &mut E {
+        &mut self.as_slice_mut()[idx]
+    }
+}
+
+impl<E, L, P> fmt::Debug for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+    E: fmt::Debug,
+    L: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("GrowableArray")
+            .field("label", &NoPrecision(self.get_label()))
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .field("elements", &ElidedSlice(self.as_slice()))
+            .finish()
+    }
+}
+
+unsafe impl<E, L, P> Send for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L> + Send,
+    E: Send,
+    L: Send,
+{
+}
+
+unsafe impl<E, L, P> Sync for GrowableArray<E, L, P>
+where
+    P: BaseArrayPtr<E, L> + Sync,
+    E: Sync,
+    L: Sync,
+{
+}
@@ -0,0 +1,75 @@
+//! Contains the definition for `LabelledSlice`, a borrowed view into an array
+//! and its label.
+
+use super::fmt_utils::{ElidedSlice, NoPrecision};
+use crate::prelude::*;
+
+/// Borrowed view into the label and elements of an array.
+///
+/// Useful for passing an array's contents and metadata to code that doesn't
+/// need to own the array, or care about which array type it is.
+///
+/// ```rust
+/// # use heaparray::*;
+/// let array = HeapArray::with_label("label", 3, |_, i| i);
+/// let slice = array.as_labelled_slice();
+/// assert!(*slice.get_label() == "label");
+/// assert!(slice.as_slice() == &[0, 1, 2]);
+/// ```
+pub struct LabelledSlice<'a, E, L> {
+    label: &'a L,
+    elements: &'a [E],
+}
+
+impl<'a, E, L> LabelledSlice<'a, E, L> {
+    /// Creates a view out of a label and a slice of elements.
+    pub fn new(label: &'a L, elements: &'a [E]) -> Self {
+        Self { label, elements }
+    }
+
+    /// Get a reference to the label of this slice.
+    pub fn get_label(&self) -> &'a L {
+        self.label
+    }
+
+    /// Get the elements of this slice.
+    pub fn as_slice(&self) -> &'a [E] {
+        self.elements
+    }
+}
+
+impl<'a, E, L> Clone for LabelledSlice<'a, E, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, E, L> Copy for LabelledSlice<'a, E, L> {}
+
+impl<'a, E, L> Container for LabelledSlice<'a, E, L> {
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+}
+
+impl<'a, E, L> Index<usize> for LabelledSlice<'a, E, L> {
+    type Output = E;
+    fn index(&self, idx: usize) -> &E {
+        &self.elements[idx]
+    }
+}
+
+impl<'a, E, L> fmt::Debug for LabelledSlice<'a, E, L>
+where
+    E: fmt::Debug,
+    L: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("LabelledSlice")
+            .field("label", &NoPrecision(self.label))
+            .field("len", &self.len())
+            .field("elements", &ElidedSlice(self.elements))
+            .finish()
+    }
+}
@@ -4,9 +4,23 @@ Implementations of safe APIs to the `BaseArray` struct.
 `BaseArray` is defined in [`heaparray::base`](../base/index.html).
 */
 
+mod builder;
+mod bytes;
+mod checksum;
+mod constructors;
+mod fmt_utils;
 mod generic;
+mod growable;
+mod labelled_slice;
 mod p_types;
+mod transforms;
+mod zeroize;
 
 pub use crate::api_prelude::*;
+pub use builder::ArrayBuilder;
+pub(crate) use fmt_utils::{ElidedSlice, NoPrecision};
 pub use generic::*;
-pub use p_types::{FatPtrArray, ThinPtrArray};
+pub use growable::GrowableArray;
+pub use labelled_slice::LabelledSlice;
+pub use p_types::{FatArrayPtr, FatPtrArray, ThinArrayPtr, ThinPtrArray};
+pub use zeroize::{Zeroable, ZeroizeOnDrop};
@@ -1,5 +1,6 @@
 use super::generic::*;
 use crate::base::*;
+use core::marker::PhantomData;
 use core::ptr::NonNull;
 
 /// 1-word reference to an array on the heap that takes ownership of its contained
@@ -19,25 +20,46 @@ type ThinPtr<E, L> = NonNull<MemBlock<E, LenLabel<L>>>;
 
 /// Thin pointer to a memory block, that implements the `BaseArrayPtr` and
 /// `SafeArrayPtr` traits.
+///
+/// Blocks are allocated with `A`, which is created with `A::default()` whenever
+/// it's needed, so it should be a handle to an allocator rather than the
+/// allocator itself.
 #[repr(transparent)]
-pub struct ThinArrayPtr<E, L> {
+pub struct ThinArrayPtr<E, L, A = Global> {
     data: ThinPtr<E, L>,
+    allocator: PhantomData<A>,
 }
 
-unsafe impl<E, L> BaseArrayPtr<E, L> for ThinArrayPtr<E, L> {
+unsafe impl<E, L, A> BaseArrayPtr<E, L> for ThinArrayPtr<E, L, A>
+where
+    A: RawAllocator + Default,
+{
     unsafe fn alloc(len: usize) -> Self {
         Self {
-            data: ThinPtr::alloc(len),
+            data: NonNull::new_unchecked(MemBlock::alloc_in(len, &A::default())),
+            allocator: PhantomData,
         }
     }
 
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        MemBlock::try_alloc_in(len, &A::default()).map(|ptr| Self {
+            data: NonNull::new_unchecked(ptr),
+            allocator: PhantomData,
+        })
+    }
+
     unsafe fn dealloc(&mut self, len: usize) {
-        self.data.dealloc(len)
+        MemBlock::dealloc_in(self.data.as_ptr(), len, &A::default())
+    }
+    unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        let ptr = MemBlock::realloc_in(self.data.as_ptr(), old_len, new_len, &A::default());
+        self.data = NonNull::new_unchecked(ptr);
     }
 
     unsafe fn from_ptr(ptr: *mut u8) -> Self {
         Self {
             data: ThinPtr::from_ptr(ptr),
+            allocator: PhantomData,
         }
     }
 
@@ -56,9 +78,28 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for ThinArrayPtr<E, L> {
     fn elem_ptr(&self, idx: usize) -> *mut E {
         self.data.elem_ptr(idx)
     }
+
+    fn max_len() -> usize {
+        ThinPtr::<E, L>::max_len()
+    }
+
+    fn memory_layout(len: usize) -> (usize, usize) {
+        MemBlock::<E, LenLabel<L>>::memory_layout(len)
+    }
+
+    fn elements_offset() -> usize {
+        MemBlock::<E, LenLabel<L>>::elements_offset()
+    }
+
+    fn elements_align() -> usize {
+        MemBlock::<E, LenLabel<L>>::elements_align()
+    }
 }
 
-unsafe impl<E, L> SafeArrayPtr<E, L> for ThinArrayPtr<E, L> {
+unsafe impl<E, L, A> SafeArrayPtr<E, L> for ThinArrayPtr<E, L, A>
+where
+    A: RawAllocator + Default,
+{
     fn set_len(&mut self, len: usize) {
         unsafe { (&mut *self.data.lbl_ptr()).len = len }
     }
@@ -67,29 +108,69 @@ unsafe impl<E, L> SafeArrayPtr<E, L> for ThinArrayPtr<E, L> {
     }
 }
 
+// Array pointers own their memory blocks, like `Box`, so sending or sharing one
+// is safe whenever sending or sharing the label and elements is. Without these,
+// no array built on them, including `ArcArray`, could be sent between threads.
+unsafe impl<E, L, A> Send for ThinArrayPtr<E, L, A>
+where
+    E: Send,
+    L: Send,
+    A: Send,
+{
+}
+
+unsafe impl<E, L, A> Sync for ThinArrayPtr<E, L, A>
+where
+    E: Sync,
+    L: Sync,
+    A: Sync,
+{
+}
+
 /// Fat pointer to a memory block, that implements the `BaseArrayPtr` and
 /// `SafeArrayPtr` traits.
-pub struct FatArrayPtr<E, L> {
+///
+/// Blocks are allocated with `A`, in the same way as `ThinArrayPtr`.
+pub struct FatArrayPtr<E, L, A = Global> {
     data: NonNull<MemBlock<E, L>>,
     len: usize,
+    allocator: PhantomData<A>,
 }
 
-unsafe impl<E, L> BaseArrayPtr<E, L> for FatArrayPtr<E, L> {
+unsafe impl<E, L, A> BaseArrayPtr<E, L> for FatArrayPtr<E, L, A>
+where
+    A: RawAllocator + Default,
+{
     unsafe fn alloc(len: usize) -> Self {
         Self {
-            data: NonNull::alloc(len),
+            data: NonNull::new_unchecked(MemBlock::alloc_in(len, &A::default())),
             len: len,
+            allocator: PhantomData,
         }
     }
 
+    unsafe fn try_alloc(len: usize) -> Result<Self, TryReserveError> {
+        MemBlock::try_alloc_in(len, &A::default()).map(|ptr| Self {
+            data: NonNull::new_unchecked(ptr),
+            len,
+            allocator: PhantomData,
+        })
+    }
+
     unsafe fn dealloc(&mut self, len: usize) {
-        self.data.dealloc(len)
+        MemBlock::dealloc_in(self.data.as_ptr(), len, &A::default())
+    }
+    unsafe fn realloc(&mut self, old_len: usize, new_len: usize) {
+        let ptr = MemBlock::realloc_in(self.data.as_ptr(), old_len, new_len, &A::default());
+        self.data = NonNull::new_unchecked(ptr);
+        self.len = new_len;
     }
 
     unsafe fn from_ptr(ptr: *mut u8) -> Self {
         Self {
             data: NonNull::from_ptr(ptr),
             len: 0,
+            allocator: PhantomData,
         }
     }
 
@@ -110,7 +191,10 @@ unsafe impl<E, L> BaseArrayPtr<E, L> for FatArrayPtr<E, L> {
     }
 }
 
-unsafe impl<E, L> SafeArrayPtr<E, L> for FatArrayPtr<E, L> {
+unsafe impl<E, L, A> SafeArrayPtr<E, L> for FatArrayPtr<E, L, A>
+where
+    A: RawAllocator + Default,
+{
     fn set_len(&mut self, len: usize) {
         self.len = len;
     }
@@ -118,3 +202,19 @@ unsafe impl<E, L> SafeArrayPtr<E, L> for FatArrayPtr<E, L> {
         self.len
     }
 }
+
+unsafe impl<E, L, A> Send for FatArrayPtr<E, L, A>
+where
+    E: Send,
+    L: Send,
+    A: Send,
+{
+}
+
+unsafe impl<E, L, A> Sync for FatArrayPtr<E, L, A>
+where
+    E: Sync,
+    L: Sync,
+    A: Sync,
+{
+}
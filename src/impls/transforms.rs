@@ -0,0 +1,870 @@
+//! Methods that consume a `SafeArray` to produce something new.
+
+use super::generic::*;
+use super::p_types::{FatArrayPtr, FatPtrArray, ThinPtrArray};
+use crate::base::{BaseArray, BaseArrayIter, BaseArrayPtr, MemBlock};
+use crate::prelude::*;
+use core::ops::Add;
+use core::ptr;
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Returns this array with `value` appended to the end.
+    ///
+    /// This reallocates the array and moves its label and elements into the
+    /// new block, so it's O(n) for each call; building an array by pushing
+    /// repeatedly is quadratic.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(2, |i| i).push(2);
+    /// assert!(array.as_slice() == &[0, 1, 2]);
+    /// ```
+    pub fn push(self, value: E) -> Self {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe {
+            data.realloc(len, len + 1);
+            ptr::write(data.get_ptr_mut(len), value);
+            Self::from_base(data, len + 1)
+        }
+    }
+
+    /// Consumes this array, passing each of its elements by value to `func`,
+    /// which accumulates them into `init_label`. Returns the accumulated label.
+    ///
+    /// The label of this array is dropped, and elements are passed to `func`
+    /// in order.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u32, u64>::with_label(0, 4, |_, i| i as u32 + 1);
+    /// let sum = array.fold_into_label(0, |sum, elem| *sum += u64::from(elem));
+    /// assert!(sum == 10);
+    /// ```
+    pub fn fold_into_label<F>(self, mut init_label: L, mut func: F) -> L
+    where
+        F: FnMut(&mut L, E),
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe {
+            ptr::drop_in_place(data.get_label_mut());
+            for i in 0..len {
+                func(&mut init_label, ptr::read(data.get_ptr(i)));
+            }
+            data.drop_lazy(len);
+        }
+        init_label
+    }
+
+    /// Moves the first element of this array into the label of a new array,
+    /// whose elements are the rest of the elements of this array. Useful when
+    /// the first element is metadata, like the header of a message.
+    ///
+    /// The label of this array is dropped, and the elements are moved into a
+    /// new block, since the label of the new array has a different layout.
+    ///
+    /// # Panics
+    /// Panics if this array is empty.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(4, |i| i).promote_first_to_label();
+    /// assert!(*array.get_label() == 0);
+    /// assert!(array.as_slice() == &[1, 2, 3]);
+    /// ```
+    pub fn promote_first_to_label(self) -> FatPtrArray<E, E> {
+        let len = self.len();
+        assert!(
+            len != 0,
+            "Cannot promote the first element of an empty array to a label"
+        );
+        let mut data = self.into_base();
+        unsafe {
+            let mut out = BaseArray::new_lazy(ptr::read(data.get_ptr(0)), len - 1);
+            ptr::copy_nonoverlapping(data.get_ptr(1), out.get_ptr_mut(0), len - 1);
+            ptr::drop_in_place(data.get_label_mut());
+            data.drop_lazy(len);
+            FatPtrArray::from_base(out, len - 1)
+        }
+    }
+}
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+{
+    /// Moves the label of this array into a new array whose elements are the
+    /// result of calling `func` on each element of this array, in order.
+    fn map_into<T, Q, F>(self, mut func: F) -> SafeArray<T, L, Q>
+    where
+        Q: SafeArrayPtr<T, L>,
+        F: FnMut(E) -> T,
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        let label = unsafe { ptr::read(data.get_label()) };
+        let mut guard = MapGuard {
+            data: &mut data,
+            len,
+            consumed: 0,
+        };
+        let out = SafeArray::with_label(label, len, |_, i| {
+            guard.consumed += 1;
+            func(unsafe { ptr::read(guard.data.get_ptr(i)) })
+        });
+        mem::forget(guard);
+        unsafe { data.drop_lazy(len) };
+        out
+    }
+
+    /// Like `map_into`, but stops at the first error. On an error, the label,
+    /// the elements that were already mapped, and the elements that weren't yet
+    /// mapped are all dropped.
+    fn try_map_into<T, Q, Er, F>(self, mut func: F) -> Result<SafeArray<T, L, Q>, Er>
+    where
+        Q: SafeArrayPtr<T, L>,
+        F: FnMut(E) -> Result<T, Er>,
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        let mut guard = MapGuard {
+            data: &mut data,
+            len,
+            consumed: 0,
+        };
+        unsafe {
+            let mut out = BaseArray::new_lazy(ptr::read(guard.data.get_label()), len);
+            let mut out_guard = PartialGuard {
+                data: &mut out,
+                len,
+                initialized: 0,
+            };
+            while guard.consumed < len {
+                let i = guard.consumed;
+                guard.consumed += 1;
+                let value = func(ptr::read(guard.data.get_ptr(i)))?;
+                ptr::write(out_guard.data.get_ptr_mut(i), value);
+                out_guard.initialized += 1;
+            }
+            mem::forget(out_guard);
+            mem::forget(guard);
+            data.drop_lazy(len);
+            Ok(SafeArray::from_base(out, len))
+        }
+    }
+}
+
+impl<E, L> FatPtrArray<E, L> {
+    /// Returns a new array with the same label, whose elements are the result
+    /// of calling `func` on each element of this array, in order.
+    ///
+    /// The label and elements are moved into a new block, since the new element
+    /// type can have a different layout.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u32, &str>::with_label("label", 3, |_, i| i as u32);
+    /// let array = array.map(|elem| elem.to_string());
+    /// assert!(*array.get_label() == "label");
+    /// assert!(array.as_slice() == &["0", "1", "2"]);
+    /// ```
+    pub fn map<T, F>(self, func: F) -> FatPtrArray<T, L>
+    where
+        F: FnMut(E) -> T,
+    {
+        self.map_into(func)
+    }
+
+    /// Returns a new array with the same label, whose elements are the result
+    /// of calling `func` on each element of this array, in order, or returns
+    /// the first error `func` returns.
+    ///
+    /// On an error, the label, the elements that were already mapped, and the
+    /// elements that weren't yet mapped are all dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<&str, ()>::new(3, |i| ["1", "2", "3"][i]);
+    /// let parsed = array.try_map(|s| s.parse::<u32>()).unwrap();
+    /// assert!(parsed.as_slice() == &[1, 2, 3]);
+    ///
+    /// let array = HeapArray::<&str, ()>::new(3, |i| ["1", "x", "3"][i]);
+    /// assert!(array.try_map(|s| s.parse::<u32>()).is_err());
+    /// ```
+    pub fn try_map<T, Er, F>(self, func: F) -> Result<FatPtrArray<T, L>, Er>
+    where
+        F: FnMut(E) -> Result<T, Er>,
+    {
+        self.try_map_into(func)
+    }
+
+    /// Replaces the label of this array with the result of calling `func` on
+    /// it, keeping the same elements.
+    ///
+    /// If the new label has the same size and alignment as the old one, the
+    /// block is reused as-is; otherwise the elements are moved into a new block.
+    /// If `func` panics, the elements are dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::<u32, u32>::with_label(3, 3, |_, i| i as u32);
+    /// let array = array.relabel(|label| label.to_string());
+    /// assert!(*array.get_label() == "3");
+    /// assert!(array.as_slice() == &[0, 1, 2]);
+    /// ```
+    pub fn relabel<L2, F>(self, func: F) -> FatPtrArray<E, L2>
+    where
+        F: FnOnce(L) -> L2,
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        let label = unsafe { ptr::read(data.get_label()) };
+        let guard = MapGuard {
+            data: &mut data,
+            len,
+            consumed: 0,
+        };
+        let label = func(label);
+        mem::forget(guard);
+        unsafe {
+            if MemBlock::<E, L>::memory_layout(len) == MemBlock::<E, L2>::memory_layout(len) {
+                let ptr = FatArrayPtr::from_ptr(data.as_ptr().as_ptr());
+                let mut out = BaseArray::from_ptr(ptr);
+                ptr::write(out.get_label_mut(), label);
+                FatPtrArray::from_base(out, len)
+            } else {
+                let mut out = BaseArray::new_lazy(label, len);
+                ptr::copy_nonoverlapping(data.get_ptr(0), out.get_ptr_mut(0), len);
+                data.drop_lazy(len);
+                FatPtrArray::from_base(out, len)
+            }
+        }
+    }
+}
+
+impl<E, L> ThinPtrArray<E, L> {
+    /// Returns a new array with the same label, whose elements are the result
+    /// of calling `func` on each element of this array, in order.
+    ///
+    /// The label and elements are moved into a new block, since the new element
+    /// type can have a different layout.
+    ///
+    /// ```rust
+    /// # use heaparray::impls::*;
+    /// let array = ThinPtrArray::<u32, &str>::with_label("label", 3, |_, i| i as u32);
+    /// let array = array.map(|elem| elem.to_string());
+    /// assert!(*array.get_label() == "label");
+    /// assert!(array.as_slice() == &["0", "1", "2"]);
+    /// ```
+    pub fn map<T, F>(self, func: F) -> ThinPtrArray<T, L>
+    where
+        F: FnMut(E) -> T,
+    {
+        self.map_into(func)
+    }
+
+    /// Returns a new array with the same label, whose elements are the result
+    /// of calling `func` on each element of this array, in order, or returns
+    /// the first error `func` returns.
+    ///
+    /// On an error, the label, the elements that were already mapped, and the
+    /// elements that weren't yet mapped are all dropped.
+    pub fn try_map<T, Er, F>(self, func: F) -> Result<ThinPtrArray<T, L>, Er>
+    where
+        F: FnMut(E) -> Result<T, Er>,
+    {
+        self.try_map_into(func)
+    }
+}
+
+/// Cleans up the source array of `map_into` if the mapping function panics;
+/// the label has already been moved out.
+struct MapGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    data: &'a mut BaseArray<E, L, P>,
+    len: usize,
+    consumed: usize,
+}
+
+impl<'a, E, L, P> Drop for MapGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            for i in self.consumed..self.len {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(self.len);
+        }
+    }
+}
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Clone,
+{
+    /// Returns an array whose element at index `i` is `func(out[i - 1], self[i])`,
+    /// and whose first element is a clone of the first element of this array.
+    fn scan<F>(&self, mut func: F) -> FatPtrArray<E, ()>
+    where
+        F: FnMut(E, &E) -> E,
+    {
+        let mut prev: Option<E> = None;
+        FatPtrArray::new(self.len(), |i| {
+            let elem = match prev.take() {
+                Some(prev) => func(prev, &self[i]),
+                None => self[i].clone(),
+            };
+            prev = Some(elem.clone());
+            elem
+        })
+    }
+
+    /// Returns a new array of length `new_len` with a clone of the label of
+    /// this array. The first `min(len, new_len)` elements are clones of the
+    /// elements of this array, and the element at each index `i` after that is
+    /// `func(i)`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| i);
+    /// assert!(array.resized_with(5, |i| i * 10).as_slice() == &[0, 1, 2, 30, 40]);
+    /// assert!(array.resized_with(2, |_| unreachable!()).as_slice() == &[0, 1]);
+    /// ```
+    pub fn resized_with<F>(&self, new_len: usize, mut func: F) -> Self
+    where
+        L: Clone,
+        F: FnMut(usize) -> E,
+    {
+        let len = self.len();
+        Self::with_label(self.get_label().clone(), new_len, |_, i| {
+            if i < len {
+                self[i].clone()
+            } else {
+                func(i)
+            }
+        })
+    }
+
+    /// Returns an array whose element at index `i` is the largest of the
+    /// elements of this array at indices `0..=i`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(5, |i| [3, 1, 4, 1, 5][i]);
+    /// assert!(array.cumulative_max().as_slice() == &[3, 3, 4, 4, 5]);
+    /// ```
+    pub fn cumulative_max(&self) -> FatPtrArray<E, ()>
+    where
+        E: Ord,
+    {
+        self.scan(|max, elem| core::cmp::max(max, elem.clone()))
+    }
+
+    /// Returns an array whose element at index `i` is the smallest of the
+    /// elements of this array at indices `0..=i`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(5, |i| [3, 1, 4, 1, 5][i]);
+    /// assert!(array.cumulative_min().as_slice() == &[3, 1, 1, 1, 1]);
+    /// ```
+    pub fn cumulative_min(&self) -> FatPtrArray<E, ()>
+    where
+        E: Ord,
+    {
+        self.scan(|min, elem| core::cmp::min(min, elem.clone()))
+    }
+
+    /// Returns an array whose element at index `i` is the sum of the elements
+    /// of this array at indices `0..=i`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(5, |i| [3, 1, 4, 1, 5][i]);
+    /// assert!(array.cumulative_sum().as_slice() == &[3, 4, 8, 9, 14]);
+    /// ```
+    pub fn cumulative_sum(&self) -> FatPtrArray<E, ()>
+    where
+        E: Add<Output = E>,
+    {
+        self.scan(|sum, elem| sum + elem.clone())
+    }
+}
+
+impl<E, P> SafeArray<E, (), P>
+where
+    P: SafeArrayPtr<E, ()>,
+{
+    /// Splits this array in two, returning the first `keep` elements in a
+    /// reallocated version of this array, and the rest of the elements in a
+    /// new array. Elements are moved, not cloned.
+    ///
+    /// # Panics
+    /// Panics if `keep > len`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let (head, tail) = HeapArray::new(5, |i| i).split_tail(2);
+    /// assert!(head.as_slice() == &[0, 1]);
+    /// assert!(tail.as_slice() == &[2, 3, 4]);
+    /// ```
+    pub fn split_tail(self, keep: usize) -> (Self, Self) {
+        let len = self.len();
+        assert!(
+            keep <= len,
+            "Cannot keep {} elements of an array of length {}",
+            keep,
+            len
+        );
+        let mut data = self.into_base();
+        let tail = Self::new(len - keep, |i| unsafe { ptr::read(data.get_ptr(keep + i)) });
+        unsafe {
+            data.realloc(len, keep);
+            (Self::from_base(data, keep), tail)
+        }
+    }
+
+    /// Removes the elements in `range` from this array, returning an array of
+    /// the elements that are left and an iterator over the removed elements.
+    /// Elements are moved, not cloned.
+    ///
+    /// Removed elements that haven't been yielded when the iterator is dropped
+    /// are dropped along with it.
+    ///
+    /// # Panics
+    /// Panics if the start of `range` is after its end, or if its end is greater
+    /// than `len`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let (array, drained) = HeapArray::new(5, |i| i).drain(1..3);
+    /// assert!(array.as_slice() == &[0, 3, 4]);
+    /// assert!(drained.collect::<Vec<_>>() == vec![1, 2]);
+    /// ```
+    pub fn drain(self, range: Range<usize>) -> (Self, BaseArrayIter<E, (), P>) {
+        let len = self.len();
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "Cannot drain range {:?} from an array of length {}",
+            range,
+            len
+        );
+        let drained_len = range.end - range.start;
+        let mut data = self.into_base();
+        let drained = Self::new(drained_len, |i| unsafe {
+            ptr::read(data.get_ptr(range.start + i))
+        });
+        let kept = Self::new(len - drained_len, |i| {
+            let idx = if i < range.start { i } else { i + drained_len };
+            unsafe { ptr::read(data.get_ptr(idx)) }
+        });
+        unsafe { data.drop_lazy(len) };
+        (kept, drained.into_iter())
+    }
+
+    /// Returns a new array containing the elements of this array followed by
+    /// the elements of `other`. Elements are moved, not cloned.
+    ///
+    /// # Panics
+    /// Panics if the combined length is longer than `max_len()`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(2, |i| i).concat(HeapArray::new(3, |i| i + 2));
+    /// assert!(array.as_slice() == &[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn concat(self, other: Self) -> Self {
+        let (len, other_len) = (self.len(), other.len());
+        let new_len = match len.checked_add(other_len) {
+            Some(new_len) if new_len <= Self::max_len() => new_len,
+            _ => panic!(
+                "Cannot concatenate arrays of lengths {} and {}: the result would be \
+                 longer than the maximum length of {}",
+                len,
+                other_len,
+                Self::max_len()
+            ),
+        };
+        let mut data = self.into_base();
+        let mut other = other.into_base();
+        unsafe {
+            data.realloc(len, new_len);
+            ptr::copy_nonoverlapping(other.get_ptr(0), data.get_ptr_mut(len), other_len);
+            other.drop_lazy(other_len);
+            Self::from_base(data, new_len)
+        }
+    }
+
+    /// Returns a new array containing the elements of this array repeated `n`
+    /// times.
+    ///
+    /// # Panics
+    /// Panics if the length of the new array would overflow.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(2, |i| i + 1);
+    /// assert!(array.repeat(3).as_slice() == &[1, 2, 1, 2, 1, 2]);
+    /// ```
+    pub fn repeat(&self, n: usize) -> Self
+    where
+        E: Clone,
+    {
+        let len = self.len();
+        let new_len = len
+            .checked_mul(n)
+            .expect("Length of repeated array overflows `usize`");
+        Self::new(new_len, |i| self[i % len].clone())
+    }
+
+    /// Distributes the elements of this array round-robin into `N` new arrays,
+    /// so that the element at index `i` ends up in the array at index `i % N`.
+    /// Elements are moved, not cloned.
+    ///
+    /// The first `len % N` arrays have one more element than the rest.
+    ///
+    /// # Panics
+    /// Panics if `N == 0`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let [evens, odds] = HeapArray::new(5, |i| i).scatter::<2>();
+    /// assert!(evens.as_slice() == &[0, 2, 4]);
+    /// assert!(odds.as_slice() == &[1, 3]);
+    /// ```
+    pub fn scatter<const N: usize>(self) -> [Self; N] {
+        assert!(N != 0, "Cannot scatter an array into 0 arrays");
+        let len = self.len();
+        let mut data = self.into_base();
+        let parts = core::array::from_fn(|part| {
+            let part_len = len / N + if part < len % N { 1 } else { 0 };
+            Self::new(part_len, |i| unsafe {
+                ptr::read(data.get_ptr(i * N + part))
+            })
+        });
+        unsafe { data.drop_lazy(len) };
+        parts
+    }
+
+    /// Returns an array containing only the elements of this array for which
+    /// `pred` returns `true`, in the same order. Kept elements are moved, not
+    /// cloned, and the rest are dropped.
+    ///
+    /// The elements are compacted within the block of this array, which is then
+    /// reallocated to the new length.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(6, |i| i).filter_collect(|i| i % 2 == 0);
+    /// assert!(array.as_slice() == &[0, 2, 4]);
+    /// ```
+    pub fn filter_collect<F>(self, mut pred: F) -> Self
+    where
+        F: FnMut(&E) -> bool,
+    {
+        self.compact(|elem, _| pred(elem))
+    }
+
+    /// Returns an array where every run of consecutive equal elements of this
+    /// array is replaced by its first element. Kept elements are moved, not
+    /// cloned, and the rest are dropped.
+    ///
+    /// Like `filter_collect`, the elements are compacted within the block of
+    /// this array, which is then reallocated to the new length.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(7, |i| [1, 1, 2, 3, 3, 3, 1][i]).dedup();
+    /// assert!(array.as_slice() == &[1, 2, 3, 1]);
+    /// ```
+    pub fn dedup(self) -> Self
+    where
+        E: PartialEq,
+    {
+        self.dedup_by(|elem, last| elem == last)
+    }
+
+    /// Returns an array where every run of consecutive elements that map to
+    /// the same key is replaced by its first element.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(6, |i| i).dedup_by_key(|i| *i / 2);
+    /// assert!(array.as_slice() == &[0, 2, 4]);
+    /// ```
+    pub fn dedup_by_key<K, F>(self, mut key: F) -> Self
+    where
+        K: PartialEq,
+        F: FnMut(&mut E) -> K,
+    {
+        self.dedup_by(|elem, last| key(elem) == key(last))
+    }
+
+    /// Returns an array where every run of consecutive elements that
+    /// `same_bucket` considers equal is replaced by its first element.
+    ///
+    /// `same_bucket` is passed each element and the last element that was
+    /// kept before it, in that order, and should return `true` if the element
+    /// should be removed.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let words = ["foo", "Foo", "bar", "BAR", "foo"];
+    /// let array = HeapArray::new(5, |i| words[i].to_string());
+    /// let array = array.dedup_by(|elem, last| elem.eq_ignore_ascii_case(last));
+    /// assert!(array.as_slice() == &["foo", "bar", "foo"]);
+    /// ```
+    pub fn dedup_by<F>(self, mut same_bucket: F) -> Self
+    where
+        F: FnMut(&mut E, &mut E) -> bool,
+    {
+        self.compact(|elem, last| match last {
+            Some(last) => !same_bucket(elem, last),
+            None => true,
+        })
+    }
+
+    /// Moves the elements for which `keep` returns `true` to the front of the
+    /// block, dropping the rest, and reallocates the block to the new length.
+    /// `keep` is passed each element and the last element that was kept before
+    /// it, if there is one.
+    fn compact<F>(self, mut keep: F) -> Self
+    where
+        F: FnMut(&mut E, Option<&mut E>) -> bool,
+    {
+        let len = self.len();
+        let mut data = self.into_base();
+        let mut guard = FilterGuard {
+            data: &mut data,
+            len,
+            processed: 0,
+            kept: 0,
+        };
+        unsafe {
+            while guard.processed < len {
+                let i = guard.processed;
+                let elem = &mut *guard.data.get_ptr_mut(i);
+                let last = match guard.kept {
+                    0 => None,
+                    kept => Some(&mut *guard.data.get_ptr_mut(kept - 1)),
+                };
+                let keep = keep(elem, last);
+                guard.processed += 1;
+                if keep {
+                    ptr::copy(guard.data.get_ptr(i), guard.data.get_ptr_mut(guard.kept), 1);
+                    guard.kept += 1;
+                } else {
+                    ptr::drop_in_place(guard.data.get_ptr_mut(i));
+                }
+            }
+            let kept = guard.kept;
+            mem::forget(guard);
+            data.realloc(len, kept);
+            Self::from_base(data, kept)
+        }
+    }
+
+    /// Moves the elements of this array into a fixed-size array, or returns
+    /// this array unchanged if its length isn't `N`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| i);
+    /// let array = array.into_array::<4>().unwrap_err();
+    /// assert!(array.into_array::<3>().ok() == Some([0, 1, 2]));
+    /// ```
+    pub fn into_array<const N: usize>(self) -> Result<[E; N], Self> {
+        if self.len() != N {
+            return Err(self);
+        }
+        let mut data = self.into_base();
+        unsafe {
+            let out = ptr::read(data.get_ptr(0) as *const [E; N]);
+            data.drop_lazy(N);
+            Ok(out)
+        }
+    }
+}
+
+/// Cleans up an array whose label and first `initialized` elements have been
+/// initialized, if initializing the rest of it fails.
+struct PartialGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    data: &'a mut BaseArray<E, L, P>,
+    len: usize,
+    initialized: usize,
+}
+
+impl<'a, E, L, P> Drop for PartialGuard<'a, E, L, P>
+where
+    P: BaseArrayPtr<E, L>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.data.get_label_mut());
+            for i in 0..self.initialized {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(self.len);
+        }
+    }
+}
+
+/// Cleans up an array that's partway through `filter_collect` if the predicate
+/// panics.
+struct FilterGuard<'a, E, P>
+where
+    P: BaseArrayPtr<E, ()>,
+{
+    data: &'a mut BaseArray<E, (), P>,
+    len: usize,
+    processed: usize,
+    kept: usize,
+}
+
+impl<'a, E, P> Drop for FilterGuard<'a, E, P>
+where
+    P: BaseArrayPtr<E, ()>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            for i in 0..self.kept {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            for i in self.processed..self.len {
+                ptr::drop_in_place(self.data.get_ptr_mut(i));
+            }
+            self.data.drop_lazy(self.len);
+        }
+    }
+}
+
+impl<E> FatPtrArray<E, ()> {
+    /// Converts this array into a boxed slice.
+    ///
+    /// The allocation of this array is reused whenever `E` isn't zero-sized and
+    /// the array isn't empty, since then the layout of the array matches the
+    /// layout of a boxed slice. Otherwise the elements are copied into a new
+    /// allocation in bulk.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| i);
+    /// let ptr = array.as_slice().as_ptr();
+    /// let boxed = array.into_boxed_slice();
+    /// assert!(&*boxed == &[0, 1, 2]);
+    /// assert!(boxed.as_ptr() == ptr);
+    /// ```
+    pub fn into_boxed_slice(self) -> Box<[E]> {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe {
+            if Self::shares_slice_layout(len) {
+                let slice = ptr::slice_from_raw_parts_mut(data.get_ptr_mut(0), len);
+                Box::from_raw(slice)
+            } else {
+                let mut vec = Vec::with_capacity(len);
+                ptr::copy_nonoverlapping(data.get_ptr(0), vec.as_mut_ptr(), len);
+                vec.set_len(len);
+                data.drop_lazy(len);
+                vec.into_boxed_slice()
+            }
+        }
+    }
+}
+
+impl<T, Err> FatPtrArray<Result<T, Err>, ()> {
+    /// Converts an array of `Result`s into an array of the `Ok` values, or
+    /// returns the first `Err` in the array. Like collecting an iterator of
+    /// `Result`s into a `Result<Vec<T>, Err>`, but without the `Vec`.
+    ///
+    /// If an `Err` is found, the values that were already unwrapped and the rest
+    /// of the array are dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array: HeapArray<Result<u32, &str>, ()> = HeapArray::new(3, |i| Ok(i as u32));
+    /// assert!(array.transpose_result().unwrap().as_slice() == &[0, 1, 2]);
+    ///
+    /// let array = HeapArray::new(3, |i| if i == 1 { Err("bad") } else { Ok(i) });
+    /// assert!(array.transpose_result().unwrap_err() == "bad");
+    /// ```
+    pub fn transpose_result(self) -> Result<FatPtrArray<T, ()>, Err> {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe {
+            let mut out = BaseArray::new_lazy((), len);
+            for i in 0..len {
+                match ptr::read(data.get_ptr(i)) {
+                    Ok(value) => ptr::write(out.get_ptr_mut(i), value),
+                    Err(err) => {
+                        for j in 0..i {
+                            ptr::drop_in_place(out.get_ptr_mut(j));
+                        }
+                        for j in (i + 1)..len {
+                            ptr::drop_in_place(data.get_ptr_mut(j));
+                        }
+                        out.drop_lazy(len);
+                        data.drop_lazy(len);
+                        return Err(err);
+                    }
+                }
+            }
+            data.drop_lazy(len);
+            Ok(FatPtrArray::from_base(out, len))
+        }
+    }
+}
+
+impl<T> FatPtrArray<Option<T>, ()> {
+    /// Converts an array of `Option`s into an array of the values they hold, or
+    /// returns `None` if any of them are `None`.
+    ///
+    /// If a `None` is found, the values that were already unwrapped and the rest
+    /// of the array are dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = HeapArray::new(3, |i| Some(i));
+    /// assert!(array.transpose_option().unwrap().as_slice() == &[0, 1, 2]);
+    ///
+    /// let array = HeapArray::new(3, |i| if i == 1 { None } else { Some(i) });
+    /// assert!(array.transpose_option().is_none());
+    /// ```
+    pub fn transpose_option(self) -> Option<FatPtrArray<T, ()>> {
+        let len = self.len();
+        let mut data = self.into_base();
+        unsafe {
+            let mut out = BaseArray::new_lazy((), len);
+            for i in 0..len {
+                match ptr::read(data.get_ptr(i)) {
+                    Some(value) => ptr::write(out.get_ptr_mut(i), value),
+                    None => {
+                        for j in 0..i {
+                            ptr::drop_in_place(out.get_ptr_mut(j));
+                        }
+                        for j in (i + 1)..len {
+                            ptr::drop_in_place(data.get_ptr_mut(j));
+                        }
+                        out.drop_lazy(len);
+                        data.drop_lazy(len);
+                        return None;
+                    }
+                }
+            }
+            data.drop_lazy(len);
+            Some(FatPtrArray::from_base(out, len))
+        }
+    }
+}
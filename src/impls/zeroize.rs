@@ -0,0 +1,130 @@
+//! Contains `ZeroizeOnDrop`, a wrapper that clears sensitive array data before
+//! it's deallocated.
+
+use super::generic::*;
+use crate::prelude::*;
+use core::ops::Deref;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// Types for which a value made entirely of zero bytes is valid.
+///
+/// # Safety
+/// Implementors must guarrantee that overwriting a value of this type with zero
+/// bytes results in a valid value, which is then safe to drop.
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable {
+    ($($t:ty),*) => {
+        $(unsafe impl Zeroable for $t {})*
+    };
+}
+
+impl_zeroable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    /// Overwrites every element in this array with zero bytes.
+    ///
+    /// The writes are volatile, so the compiler won't optimize them away even
+    /// if the array is never read again.
+    pub fn zeroize(&mut self) {
+        let bytes = self.len() * mem::size_of::<E>();
+        let ptr = self.as_slice_mut().as_mut_ptr() as *mut u8;
+        for i in 0..bytes {
+            unsafe { core::ptr::write_volatile(ptr.add(i), 0) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+/// Wrapper around a `SafeArray` that zeroes its elements before they're dropped
+/// and deallocated; useful for holding secrets like key material.
+///
+/// The wrapper dereferences to the array, but only gives mutable access to its
+/// elements, through `as_slice_mut` and indexing, so the array can't be moved
+/// out of the wrapper without being zeroed.
+///
+/// ```rust
+/// use heaparray::*;
+/// use heaparray::impls::ZeroizeOnDrop;
+///
+/// let mut key = ZeroizeOnDrop::new(HeapArray::<u8, ()>::new(32, |_| 0xff));
+/// key[0] = 0x42;
+/// key.as_slice_mut()[1] = 0x43;
+/// assert!(key.len() == 32 && key[1] == 0x43);
+/// ```
+pub struct ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    array: SafeArray<E, L, P>,
+}
+
+impl<E, L, P> ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    /// Wraps an array so that it's zeroed when dropped.
+    pub fn new(array: SafeArray<E, L, P>) -> Self {
+        Self { array }
+    }
+}
+
+impl<E, L, P> Deref for ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    type Target = SafeArray<E, L, P>;
+    fn deref(&self) -> &SafeArray<E, L, P> {
+        &self.array
+    }
+}
+
+// Only the elements can be mutated, not the array itself; otherwise the array
+// could be swapped out of the wrapper and dropped without being zeroed.
+impl<E, L, P> SliceArrayMut<E> for ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    fn as_slice_mut(&mut self) -> &mut [E] {
+        self.array.as_slice_mut()
+    }
+}
+
+impl<E, L, P> Index<usize> for ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    type Output = E;
+    fn index(&self, idx: usize) -> &E {
+        &self.array[idx]
+    }
+}
+
+impl<E, L, P> IndexMut<usize> for ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    fn index_mut(&mut self, idx: usize) -> &mut E {
+        &mut self.array[idx]
+    }
+}
+
+impl<E, L, P> Drop for ZeroizeOnDrop<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Zeroable,
+{
+    fn drop(&mut self) {
+        self.array.zeroize();
+    }
+}
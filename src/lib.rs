@@ -111,6 +111,17 @@ Using `heaparray::naive_rc`, you can construct reference-counted dynamically-siz
 types. Instead of using an `Arc<Vec<Data>>`, which is two pointer indirections from
 the data you retrieve, you can use `RcArray<Data>`, which has a functionally similar
 interface, but with less indirection overhead at runtime.
+
+## `no_std` Support
+With the `no-std` feature enabled, this crate only depends on `core` and
+`alloc`. All of the other features work without the standard library, except
+for `rayon` and `serde`.
+
+Memory is allocated with the global allocator by default. To use a different
+allocator, like a bump allocator, implement `heaparray::base::RawAllocator` and
+`Default` for a handle to it, and use it as the last type parameter of
+`heaparray::impls::FatArrayPtr` or `heaparray::impls::ThinArrayPtr`, as in
+`SafeArray<E, L, FatArrayPtr<E, L, MyAllocator>>`.
 */
 
 #![cfg_attr(feature = "no-std", no_std)]
@@ -120,11 +131,19 @@ extern crate alloc;
 
 extern crate const_utils;
 extern crate containers_rs as containers;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 mod api;
 pub mod base;
 pub mod impls;
 pub mod naive_rc;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod traits;
 
 mod api_prelude {
@@ -142,6 +161,11 @@ mod prelude {
     pub(crate) use core::fmt;
     pub(crate) use core::mem;
     pub(crate) use core::ops::{Index, IndexMut, Range};
+
+    #[cfg(feature = "no-std")]
+    pub(crate) use alloc::boxed::Box;
+    #[cfg(feature = "no-std")]
+    pub(crate) use alloc::vec::Vec;
 }
 
 pub use api::*;
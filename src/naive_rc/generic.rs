@@ -3,7 +3,10 @@
 
 use super::ref_counters::*;
 pub use crate::api_prelude_rc::*;
+use crate::impls::{ElidedSlice, InsertError, LabelledSlice, NoPrecision, SafeArray, SafeArrayPtr};
 use crate::prelude::*;
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
 use core::mem::ManuallyDrop;
 use core::ptr;
@@ -36,7 +39,7 @@ where
     A: LabelledArray<E, R>,
     R: RefCounter<L>,
 {
-    fn from_ref(ptr: A) -> Self {
+    pub(super) fn from_ref(ptr: A) -> Self {
         Self {
             data: ManuallyDrop::new(ptr),
             phantom: PhantomData,
@@ -51,10 +54,49 @@ where
     pub fn ref_count(&self) -> usize {
         self.data.get_label().counter()
     }
+    /// Returns the number of strong references to the data this `RcArray`
+    /// points to. Same as `ref_count`, but named like `Rc::strong_count`.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = RcArray::new(3, |i| i);
+    /// let other = ArrayRef::clone(&array);
+    /// assert!(array.strong_count() == 2);
+    /// drop(other);
+    /// assert!(array.strong_count() == 1);
+    /// ```
+    pub fn strong_count(&self) -> usize {
+        self.ref_count()
+    }
+    /// Returns the number of weak references to the data this `RcArray` points
+    /// to. Always 0 if the reference counter doesn't track weak references.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = ArcArray::new(3, |i| i);
+    /// let weak = array.downgrade();
+    /// assert!(array.weak_count() == 1);
+    /// drop(weak);
+    /// assert!(array.weak_count() == 0);
+    /// ```
+    pub fn weak_count(&self) -> usize {
+        // The weak counter includes one reference shared by all of the strong
+        // references.
+        self.get_counter().weak_counter().saturating_sub(1)
+    }
+    /// Returns the reference counting struct of this array.
+    pub(super) fn get_counter(&self) -> &R {
+        self.data.get_label()
+    }
+    /// Returns whether this is the only reference to its data, counting weak
+    /// references.
+    fn is_unique(&self) -> bool {
+        self.data.get_label().is_unique()
+    }
     /// Returns an owned version of this array if the caller has exclusive access,
     /// or returns back this reference otherwise.
     pub fn to_owned(self) -> Result<A, Self> {
-        if self.ref_count() > 1 {
+        if !self.is_unique() {
             Err(self)
         } else {
             Ok(self.to_ref())
@@ -63,12 +105,25 @@ where
     /// Returns a mutable reference to the array if the caller has exclusive access,
     /// or `None` otherwise.
     pub fn to_mut(&mut self) -> Option<&mut A> {
-        if self.ref_count() > 1 {
+        if !self.is_unique() {
             None
         } else {
             Some(&mut *self.data)
         }
     }
+    /// Replaces the element at index `key` with `value`, returning the old
+    /// element. Unlike `insert`, gives `value` back along with the reason it
+    /// couldn't be inserted on failure: either the index was out of bounds, or
+    /// the array is referenced by another pointer.
+    pub fn try_insert(&mut self, key: usize, value: E) -> Result<E, (InsertError, E)> {
+        if !self.is_unique() {
+            return Err((InsertError::Shared, value));
+        }
+        match self.data.get_mut(key) {
+            Some(slot) => Ok(mem::replace(slot, value)),
+            None => Err((InsertError::OutOfBounds, value)),
+        }
+    }
     pub fn ref_eq(&self, other: &Self) -> bool {
         return ptr::eq(self.data.get_label(), other.data.get_label());
     }
@@ -81,8 +136,17 @@ where
 {
     /// Returns an owned version of this array if the caller has exclusive access,
     /// or copies the data otherwise.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let array = ArcArray::new(3, |i| i);
+    /// let other = ArrayRef::clone(&array);
+    /// let mut owned = array.make_owned();
+    /// owned[0] = 10;
+    /// assert!(other[0] == 0 && other.ref_count() == 1);
+    /// ```
     pub fn make_owned(self) -> A {
-        if self.ref_count() > 1 {
+        if !self.is_unique() {
             (*self.data).clone()
         } else {
             self.to_ref()
@@ -98,16 +162,166 @@ where
             phantom: PhantomData,
         }
     }
+    /// Clones the data in `source` into this array.
+    ///
+    /// Like `RcArray::clone`, the result **does not** point to the same data as
+    /// `source`. If this array has exclusive access to its data, the data is
+    /// cloned in place, so no memory is allocated when the lengths match;
+    /// otherwise a new copy of `source`'s data is made.
+    pub fn clone_from(&mut self, source: &Self) {
+        match self.to_mut() {
+            Some(data) => data.clone_from(&*source.data),
+            None => *self = source.clone(),
+        }
+    }
     /// Returns a mutable reference to the array if the caller has exclusive access,
     /// or copies the data otherwise.
+    ///
+    /// Other references to the data aren't affected by mutations made through
+    /// the copy:
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = RcArray::new(3, |i| i);
+    /// let other = ArrayRef::clone(&array);
+    /// array.make_mut()[0] = 10;
+    /// assert!(array[0] == 10 && other[0] == 0);
+    /// assert!(!array.ref_eq(&other));
+    /// ```
     pub fn make_mut(&mut self) -> &mut A {
-        if self.ref_count() > 1 {
+        if !self.is_unique() {
             *self = Self::from_ref((*self.data).clone());
         }
         &mut *self.data
     }
 }
 
+impl<A, R, E, L> RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArray<E>,
+    R: RefCounter<L>,
+{
+    /// Returns a borrowed view into the label and elements of this array.
+    pub fn as_labelled_slice(&self) -> LabelledSlice<'_, E, L> {
+        LabelledSlice::new(self.get_label(), self.as_slice())
+    }
+}
+
+impl<A, R, E, L> RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArrayMut<E>,
+    R: RefCounter<L>,
+{
+    /// Returns an iterator over mutable references to the elements of this
+    /// array if the caller has exclusive access, or `None` otherwise.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = RcArray::new(3, |i| i);
+    /// array.iter_mut().unwrap().for_each(|elem| *elem *= 2);
+    /// assert!(array.as_slice() == &[0, 2, 4]);
+    /// let other = ArrayRef::clone(&array);
+    /// assert!(array.iter_mut().is_none());
+    /// ```
+    pub fn iter_mut(&mut self) -> Option<core::slice::IterMut<'_, E>> {
+        self.to_mut().map(|array| array.as_slice_mut().iter_mut())
+    }
+
+    /// Returns a mutable slice of the elements in `range` if the caller has
+    /// exclusive access and the range is in bounds, or `None` otherwise.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = RcArray::new(5, |i| i);
+    /// array.get_mut_slice(1..3).unwrap().reverse();
+    /// assert!(array.as_slice() == &[0, 2, 1, 3, 4]);
+    /// assert!(array.get_mut_slice(4..6).is_none());
+    /// let other = ArrayRef::clone(&array);
+    /// assert!(array.get_mut_slice(0..1).is_none());
+    /// ```
+    pub fn get_mut_slice(&mut self, range: Range<usize>) -> Option<&mut [E]> {
+        self.to_mut()?.as_slice_mut().get_mut(range)
+    }
+
+    /// Returns the elements of this array, or panics if other references to
+    /// them exist.
+    fn unique_slice_mut(&mut self, method: &'static str) -> &mut [E] {
+        match self.to_mut() {
+            Some(array) => array.as_slice_mut(),
+            None => panic!(
+                "heaparray::naive_rc::RcArray::{} called on an array whose data is shared",
+                method
+            ),
+        }
+    }
+}
+
+impl<A, R, E, L> RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArrayMut<E> + Clone,
+    R: RefCounter<L>,
+{
+    /// Applies `func` to the elements of this array, first copying the data if
+    /// other references to it exist.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = RcArray::new(3, |i| i);
+    /// let other = ArrayRef::clone(&array);
+    /// array.update(|elements| elements[0] = 10);
+    /// assert!(array[0] == 10);
+    /// assert!(other[0] == 0);
+    /// ```
+    pub fn update<F>(&mut self, func: F)
+    where
+        F: FnOnce(&mut [E]),
+    {
+        func(self.make_mut().as_slice_mut())
+    }
+}
+
+impl<E, L, R, P> RcArray<SafeArray<E, R, P>, R, E, L>
+where
+    P: SafeArrayPtr<E, R>,
+    R: RefCounter<L>,
+{
+    /// Returns the maximum length of an array of this type.
+    ///
+    /// Trying to create an array longer than this will panic.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// println!("{}", ArcArray::<u64>::max_len());
+    /// ```
+    pub fn max_len() -> usize {
+        SafeArray::<E, R, P>::max_len()
+    }
+
+    /// Shortens this array to `new_len` elements if the caller has exclusive
+    /// access, dropping the rest and reallocating the array to its new length.
+    /// Returns whether the array was shortened; it isn't if other references
+    /// to its data exist, or if `new_len` is at least its length.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = RcArray::new(5, |i| i);
+    /// let other = ArrayRef::clone(&array);
+    /// assert!(!array.shrink_to(2));
+    /// drop(other);
+    /// assert!(array.shrink_to(2));
+    /// assert!(array.as_slice() == &[0, 1]);
+    /// ```
+    pub fn shrink_to(&mut self, new_len: usize) -> bool {
+        match self.to_mut() {
+            Some(array) if new_len < array.len() => {
+                array.truncate(new_len);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 impl<A, R, E, L> Clone for RcArray<A, R, E, L>
 where
     A: LabelledArray<E, R>,
@@ -147,7 +361,14 @@ where
         let ref_count = self.data.get_label().decrement();
         if ref_count == 0 {
             unsafe {
-                ptr::drop_in_place(&mut *self.data);
+                // Only the data inside the counter is dropped, so the counts stay
+                // valid for weak references until the memory is freed.
+                let drop_data = |counter: &mut R| ptr::drop_in_place(counter.get_data_mut());
+                if self.data._drop_contents(drop_data)
+                    && self.data.get_label().weak_decrement() == 0
+                {
+                    self.data._dealloc();
+                }
             }
         }
     }
@@ -179,7 +400,7 @@ where
     /// - The array is referenced by another pointer
     /// - The index given is out-of-bounds
     fn get_mut(&mut self, key: usize) -> Option<&mut E> {
-        if self.data.get_label().counter() == 1 {
+        if self.is_unique() {
             self.data.get_mut(key)
         } else {
             None
@@ -192,11 +413,7 @@ where
     /// - The index given is out-of-bounds
     /// - There was nothing in the slot previously
     fn insert(&mut self, key: usize, value: E) -> Option<E> {
-        if self.data.get_label().counter() == 1 {
-            self.data.insert(key, value)
-        } else {
-            None
-        }
+        self.try_insert(key, value).ok()
     }
 }
 
@@ -246,6 +463,18 @@ where
     }
 }
 
+/// Creates an empty array with a default label.
+impl<A, R, E, L> Default for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R>,
+    R: RefCounter<L>,
+    L: Default,
+{
+    fn default() -> Self {
+        Self::with_label(L::default(), 0, |_, _| unreachable!())
+    }
+}
+
 impl<A, R, E, L> SliceArray<E> for RcArray<A, R, E, L>
 where
     A: LabelledArray<E, R> + SliceArray<E>,
@@ -256,6 +485,75 @@ where
     }
 }
 
+impl<A, R, E, L> AsRef<[E]> for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArray<E>,
+    R: RefCounter<L>,
+{
+    fn as_ref(&self) -> &[E] {
+        self.as_slice()
+    }
+}
+
+/// Only implemented for arrays without a label, so that `Hash` and `Eq` agree
+/// with the implementations for `[E]`; see the implementation of `Borrow` for
+/// `SafeArray`.
+impl<A, R, E> Borrow<[E]> for RcArray<A, R, E, ()>
+where
+    A: LabelledArray<E, R> + SliceArray<E>,
+    R: RefCounter<()>,
+{
+    fn borrow(&self) -> &[E] {
+        self.as_slice()
+    }
+}
+
+/// Panics if other references to the data of this array exist.
+impl<A, R, E, L> MutSliceOps<E> for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArrayMut<E>,
+    R: RefCounter<L>,
+{
+    fn fill(&mut self, value: E)
+    where
+        E: Clone,
+    {
+        self.unique_slice_mut("fill").fill(value)
+    }
+
+    fn fill_with<F>(&mut self, func: F)
+    where
+        F: FnMut() -> E,
+    {
+        self.unique_slice_mut("fill_with").fill_with(func)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.unique_slice_mut("swap").swap(i, j)
+    }
+
+    fn sort(&mut self)
+    where
+        E: Ord,
+    {
+        self.unique_slice_mut("sort").sort()
+    }
+
+    fn sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&E, &E) -> core::cmp::Ordering,
+    {
+        self.unique_slice_mut("sort_by").sort_by(compare)
+    }
+
+    fn sort_unstable(&mut self)
+    where
+        E: Ord,
+    {
+        self.unique_slice_mut("sort_unstable").sort_unstable()
+    }
+}
+
 impl<A, R, E, L> Index<Range<usize>> for RcArray<A, R, E, L>
 where
     A: LabelledArray<E, R> + SliceArray<E>,
@@ -291,6 +589,32 @@ where
     }
 }
 
+// Compares only the elements of the array with the elements of a slice, since
+// slices don't have a label.
+macro_rules! impl_slice_eq {
+    ($([$($params:tt)*] $rhs:ty),*) => {
+        $(
+            impl<$($params)* A, R, E, L, E2> PartialEq<$rhs> for RcArray<A, R, E, L>
+            where
+                A: LabelledArray<E, R> + SliceArray<E>,
+                R: RefCounter<L>,
+                E: PartialEq<E2>,
+            {
+                fn eq(&self, other: &$rhs) -> bool {
+                    self.as_slice() == &other[..]
+                }
+            }
+        )*
+    };
+}
+
+impl_slice_eq!(
+    [] [E2],
+    ['a,] &'a [E2],
+    [] Vec<E2>,
+    [const N: usize,] [E2; N]
+);
+
 impl<'a, A, R, E, L> Eq for RcArray<A, R, E, L>
 where
     A: LabelledArray<E, R> + SliceArray<E> + Eq,
@@ -298,6 +622,18 @@ where
 {
 }
 
+/// Hashes the data this array points to, so arrays that are equal hash equally
+/// even if they don't point to the same data.
+impl<A, R, E, L> Hash for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + Hash,
+    R: RefCounter<L>,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
 impl<A, R, E, L> fmt::Debug for RcArray<A, R, E, L>
 where
     A: LabelledArray<E, R> + SliceArray<E>,
@@ -310,10 +646,10 @@ where
         // name of the struct.
         formatter
             .debug_struct("RcArray")
-            .field("label", &self.get_label())
+            .field("label", &NoPrecision(self.get_label()))
             .field("ref_count", &self.ref_count())
             .field("len", &self.len())
-            .field("elements", &self.as_slice())
+            .field("elements", &ElidedSlice(self.as_slice()))
             .finish()
     }
 }
@@ -5,6 +5,7 @@
 pub mod generic;
 pub mod ref_counters;
 mod types;
+pub mod weak;
 
 pub use crate::api_prelude_rc::*;
 pub use types::*;
@@ -5,6 +5,7 @@
 //! so that the other reference counting structs can just call the API. Since
 //! all functions are `#[inline]`, this ends up being a zero-cost abstraction.
 use core::cell::Cell;
+use core::hash::{Hash, Hasher};
 use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// Utility struct that handles reference counting.
@@ -24,6 +25,47 @@ pub trait RefCounter<T> {
     fn get_data(&self) -> &T;
     /// Returns a mutable reference to the data associated with this struct.
     fn get_data_mut(&mut self) -> &mut T;
+    /// Increments the reference counter by one if it isn't zero, and returns
+    /// whether it was incremented. Used to upgrade weak references.
+    fn increment_nonzero(&self) -> bool {
+        if self.counter() == 0 {
+            false
+        } else {
+            self.increment();
+            true
+        }
+    }
+    /// Increments the weak reference counter by one and returns its current
+    /// value.
+    ///
+    /// Defaults to returning 0, for counters that don't support weak references.
+    fn weak_increment(&self) -> usize {
+        0
+    }
+    /// Decrements the weak reference counter by one and returns its current
+    /// value. The weak reference count includes one reference that's shared by
+    /// all of the strong references.
+    ///
+    /// Defaults to returning 0, for counters that don't support weak references.
+    fn weak_decrement(&self) -> usize {
+        0
+    }
+    /// Returns the weak reference count associated with this struct, including
+    /// the reference that's shared by all of the strong references.
+    ///
+    /// Defaults to returning 0, for counters that don't support weak references.
+    fn weak_counter(&self) -> usize {
+        0
+    }
+    /// Returns whether there's exactly one strong reference and no weak
+    /// references, so the caller can safely get mutable access to the data.
+    ///
+    /// Defaults to checking `counter` and then `weak_counter`, which is only
+    /// correct for counters that can't be shared between threads. Atomic
+    /// counters need to make sure no weak reference is upgraded in between.
+    fn is_unique(&self) -> bool {
+        self.counter() == 1 && self.weak_counter() <= 1
+    }
 }
 
 /// Reference counting struct for non-atomic reference counts.
@@ -39,6 +81,33 @@ where
     fn clone(&self) -> Self {
         Self::new(self.data.clone())
     }
+    /// Clones only the data of `source`, keeping the reference counts of this
+    /// struct.
+    fn clone_from(&mut self, source: &Self) {
+        self.data.clone_from(&source.data);
+    }
+}
+
+/// Compares the data of the two structs, ignoring their reference counts.
+impl<T> PartialEq for RcStruct<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data.eq(&other.data)
+    }
+}
+
+impl<T> Eq for RcStruct<T> where T: Eq {}
+
+/// Hashes the data of this struct, ignoring its reference count.
+impl<T> Hash for RcStruct<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
 }
 
 impl<T> RefCounter<T> for RcStruct<T> {
@@ -73,9 +142,116 @@ impl<T> RefCounter<T> for RcStruct<T> {
     }
 }
 
+/// Reference counting struct for non-atomic reference counts, that also keeps
+/// track of weak references.
+pub struct RcStructWeak<T> {
+    counter: Cell<usize>,
+    weak_counter: Cell<usize>,
+    pub data: T,
+}
+
+impl<T> Clone for RcStructWeak<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.data.clone())
+    }
+    /// Clones only the data of `source`, keeping the reference counts of this
+    /// struct.
+    fn clone_from(&mut self, source: &Self) {
+        self.data.clone_from(&source.data);
+    }
+}
+
+/// Compares the data of the two structs, ignoring their reference counts.
+impl<T> PartialEq for RcStructWeak<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data.eq(&other.data)
+    }
+}
+
+impl<T> Eq for RcStructWeak<T> where T: Eq {}
+
+/// Hashes the data of this struct, ignoring its reference counts.
+impl<T> Hash for RcStructWeak<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
+impl<T> RefCounter<T> for RcStructWeak<T> {
+    fn new(data: T) -> Self {
+        Self {
+            counter: Cell::new(1),
+            weak_counter: Cell::new(1),
+            data,
+        }
+    }
+    fn decrement(&self) -> usize {
+        self.counter.set(self.counter.get() - 1);
+        self.counter.get()
+    }
+    fn increment(&self) -> usize {
+        #[cfg(not(feature = "ref-counter-skip-overflow-check"))]
+        assert!(
+            self.counter.get() < usize::MAX,
+            "Incrementing the reference count of an `RcStructWeak`\
+             past `core::usize::MAX` is unsafe and results in undefined behavior"
+        );
+        self.counter.set(self.counter.get() + 1);
+        self.counter.get()
+    }
+    fn counter(&self) -> usize {
+        self.counter.get()
+    }
+    fn get_data(&self) -> &T {
+        &self.data
+    }
+    fn get_data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+    fn weak_increment(&self) -> usize {
+        #[cfg(not(feature = "ref-counter-skip-overflow-check"))]
+        assert!(
+            self.weak_counter.get() < usize::MAX,
+            "Incrementing the weak reference count of an `RcStructWeak`\
+             past `core::usize::MAX` is unsafe and results in undefined behavior"
+        );
+        self.weak_counter.set(self.weak_counter.get() + 1);
+        self.weak_counter.get()
+    }
+    fn weak_decrement(&self) -> usize {
+        self.weak_counter.set(self.weak_counter.get() - 1);
+        self.weak_counter.get()
+    }
+    fn weak_counter(&self) -> usize {
+        self.weak_counter.get()
+    }
+}
+
+/// Reference counting struct for atomic reference counts, that also keeps track
+/// of weak references. Since `ArcStruct` already tracks both counts, this is the
+/// same type.
+pub type ArcStructWeak<T> = ArcStruct<T>;
+
+/// Value of the weak count of an `ArcStruct` while `is_unique` is checking the
+/// strong count.
+const WEAK_LOCKED: usize = usize::MAX;
+
 /// Reference counting struct for atomic reference counts.
+///
+/// Also keeps track of weak references, which keep the memory of an array alive
+/// but not its contents.
 pub struct ArcStruct<T> {
     ref_count: AtomicUsize,
+    weak_count: AtomicUsize,
     pub data: T,
 }
 
@@ -86,12 +262,40 @@ where
     fn clone(&self) -> Self {
         Self::new(self.data.clone())
     }
+    /// Clones only the data of `source`, keeping the reference counts of this
+    /// struct.
+    fn clone_from(&mut self, source: &Self) {
+        self.data.clone_from(&source.data);
+    }
+}
+
+/// Compares the data of the two structs, ignoring their reference counts.
+impl<T> PartialEq for ArcStruct<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data.eq(&other.data)
+    }
+}
+
+impl<T> Eq for ArcStruct<T> where T: Eq {}
+
+/// Hashes the data of this struct, ignoring its reference count.
+impl<T> Hash for ArcStruct<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
 }
 
 impl<T> RefCounter<T> for ArcStruct<T> {
     fn new(data: T) -> Self {
         Self {
             ref_count: AtomicUsize::new(1),
+            weak_count: AtomicUsize::new(1),
             data,
         }
     }
@@ -116,6 +320,76 @@ impl<T> RefCounter<T> for ArcStruct<T> {
     fn get_data_mut(&mut self) -> &mut T {
         &mut self.data
     }
+    fn increment_nonzero(&self) -> bool {
+        let mut count = self.ref_count.load(Ordering::Relaxed);
+        loop {
+            if count == 0 {
+                return false;
+            }
+            match self.ref_count.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(current) => count = current,
+            }
+        }
+    }
+    fn weak_increment(&self) -> usize {
+        let mut count = self.weak_count.load(Ordering::Relaxed);
+        loop {
+            // `is_unique` has locked the weak count; wait for it to be released.
+            if count == WEAK_LOCKED {
+                core::hint::spin_loop();
+                count = self.weak_count.load(Ordering::Relaxed);
+                continue;
+            }
+            #[cfg(not(feature = "ref-counter-skip-overflow-check"))]
+            assert!(
+                count < WEAK_LOCKED - 1,
+                "Incrementing the weak reference count of an `ArcStruct`\
+                 past `core::usize::MAX` is unsafe and results in undefined behavior"
+            );
+            match self.weak_count.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return count + 1,
+                Err(current) => count = current,
+            }
+        }
+    }
+    fn weak_decrement(&self) -> usize {
+        self.weak_count.fetch_sub(1, Ordering::AcqRel) - 1
+    }
+    fn weak_counter(&self) -> usize {
+        match self.weak_count.load(Ordering::Acquire) {
+            // Only locked while the weak count is 1.
+            WEAK_LOCKED => 1,
+            count => count,
+        }
+    }
+    /// Locks the weak count while checking the strong count, like
+    /// `Arc::is_unique`. While the weak count is 1 there are no weak
+    /// references, so none can be upgraded, and the lock stops new ones from
+    /// being created by `weak_increment` until the strong count is checked.
+    fn is_unique(&self) -> bool {
+        if self
+            .weak_count
+            .compare_exchange(1, WEAK_LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let unique = self.ref_count.load(Ordering::Acquire) == 1;
+            self.weak_count.store(1, Ordering::Release);
+            unique
+        } else {
+            false
+        }
+    }
 }
 
 unsafe impl<T> Send for ArcStruct<T> where T: Send {}
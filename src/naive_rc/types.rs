@@ -1,5 +1,6 @@
 use super::generic::RcArray;
 use super::ref_counters::{ArcStruct, RcStruct};
+use super::weak::WeakRcArray;
 use crate::impls::{FatPtrArray, ThinPtrArray};
 
 /// Atomically reference counted array, referenced using a fat pointer.
@@ -25,3 +26,15 @@ pub type TpArcArray<E, L = ()> = RcArray<ThinPtrArray<E, ArcStruct<L>>, ArcStruc
 /// See the documentation for `heaparray::naive_rc::generic::RcArray`
 /// for more information on API.
 pub type TpRcArray<E, L = ()> = RcArray<ThinPtrArray<E, RcStruct<L>>, RcStruct<L>, E, L>;
+
+/// Weak reference to the data of an `FpArcArray`.
+///
+/// See the documentation for `heaparray::naive_rc::weak::WeakRcArray`
+/// for more information on API.
+pub type FpWeakArcArray<E, L = ()> = WeakRcArray<FatPtrArray<E, ArcStruct<L>>, ArcStruct<L>, E, L>;
+
+/// Weak reference to the data of a `TpArcArray`.
+///
+/// See the documentation for `heaparray::naive_rc::weak::WeakRcArray`
+/// for more information on API.
+pub type TpWeakArcArray<E, L = ()> = WeakRcArray<ThinPtrArray<E, ArcStruct<L>>, ArcStruct<L>, E, L>;
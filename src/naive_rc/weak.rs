@@ -0,0 +1,132 @@
+//! Contains definition for `WeakRcArray`, a weak reference to the data of an
+//! `RcArray`.
+
+use super::generic::RcArray;
+use super::ref_counters::*;
+use crate::impls::{SafeArray, SafeArrayPtr};
+use crate::prelude::*;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+
+/// Weak reference to the data of an `RcArray`, that doesn't keep its label or
+/// elements alive.
+///
+/// Created using `RcArray::downgrade`. While a weak reference exists, the memory
+/// block of the array stays allocated, but the label and elements are dropped as
+/// soon as the last strong reference is. Call `upgrade` to get a strong
+/// reference back, if the data still exists.
+///
+/// Only arrays counted with `ArcStruct` or `RcStructWeak` can be downgraded,
+/// since the other reference counting structs don't track weak references.
+///
+/// ```rust
+/// # use heaparray::*;
+/// let array = ArcArray::new(3, |i| i);
+/// let weak = array.downgrade();
+/// assert!(weak.upgrade().unwrap()[1] == 1);
+/// drop(array);
+/// assert!(weak.upgrade().is_none());
+/// ```
+#[repr(transparent)]
+pub struct WeakRcArray<A, R, E, L = ()>
+where
+    A: LabelledArray<E, R>,
+    R: RefCounter<L>,
+{
+    data: ManuallyDrop<A>,
+    phantom: PhantomData<(R, E, L)>,
+}
+
+/// Implements weak references for arrays whose reference counting struct
+/// tracks weak references as well as strong ones.
+macro_rules! impl_weak {
+    ($($counter:ident),*) => {
+        $(
+            impl<E, L, P> RcArray<SafeArray<E, $counter<L>, P>, $counter<L>, E, L>
+            where
+                P: SafeArrayPtr<E, $counter<L>>,
+            {
+                /// Creates a new weak reference to the data of this array.
+                pub fn downgrade(&self) -> WeakRcArray<SafeArray<E, $counter<L>, P>, $counter<L>, E, L> {
+                    self.get_counter().weak_increment();
+                    WeakRcArray {
+                        data: unsafe { mem::transmute_copy(self) },
+                        phantom: PhantomData,
+                    }
+                }
+            }
+
+            impl<E, L, P> WeakRcArray<SafeArray<E, $counter<L>, P>, $counter<L>, E, L>
+            where
+                P: SafeArrayPtr<E, $counter<L>>,
+            {
+                /// Returns a strong reference to the data this weak reference points
+                /// to, or `None` if all strong references to it have been dropped.
+                pub fn upgrade(&self) -> Option<RcArray<SafeArray<E, $counter<L>, P>, $counter<L>, E, L>> {
+                    if self.data.get_label().increment_nonzero() {
+                        Some(RcArray::from_ref(unsafe { mem::transmute_copy(&self.data) }))
+                    } else {
+                        None
+                    }
+                }
+                /// Returns the number of strong references to the data this weak
+                /// reference points to.
+                pub fn strong_count(&self) -> usize {
+                    self.data.get_label().counter()
+                }
+            }
+
+            impl<E, L, P> Clone for WeakRcArray<SafeArray<E, $counter<L>, P>, $counter<L>, E, L>
+            where
+                P: SafeArrayPtr<E, $counter<L>>,
+            {
+                fn clone(&self) -> Self {
+                    self.data.get_label().weak_increment();
+                    unsafe { mem::transmute_copy(self) }
+                }
+            }
+        )*
+    };
+}
+
+impl_weak!(ArcStruct, RcStructWeak);
+
+impl<A, R, E, L> Drop for WeakRcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R>,
+    R: RefCounter<L>,
+{
+    fn drop(&mut self) {
+        if self.data.get_label().weak_decrement() == 0 {
+            unsafe { self.data._dealloc() };
+        }
+    }
+}
+
+impl<A, R, E, L> fmt::Debug for WeakRcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R>,
+    R: RefCounter<L>,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("(WeakRcArray)")
+    }
+}
+
+unsafe impl<A, R, E, L> Send for WeakRcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + Send + Sync,
+    R: RefCounter<L> + Send + Sync,
+    E: Send + Sync,
+    L: Send + Sync,
+{
+}
+
+unsafe impl<A, R, E, L> Sync for WeakRcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + Send + Sync,
+    R: RefCounter<L> + Send + Sync,
+    E: Send + Sync,
+    L: Send + Sync,
+{
+}
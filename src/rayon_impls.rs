@@ -0,0 +1,96 @@
+//! Constructors for `SafeArray` that use `rayon` to do work in parallel, and
+//! implementations of `rayon`'s parallel iterator traits for arrays.
+
+use crate::base::BaseArray;
+use crate::impls::{SafeArray, SafeArrayPtr};
+use crate::naive_rc::generic::RcArray;
+use crate::naive_rc::ref_counters::RefCounter;
+use crate::prelude::*;
+use core::ptr;
+use rayon::prelude::*;
+
+impl<E, L, P> SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Send,
+{
+    /// Creates a new array of length `len`, whose elements are initialized in
+    /// parallel using `func`, and whose label is computed by calling `reduce`
+    /// on the finished elements.
+    ///
+    /// The element at index `i` is always `func(i)`, but the order in which
+    /// `func` is called is implementation-defined. `reduce` is called once,
+    /// after all elements have been initialized, and can itself use `rayon` to
+    /// do its work in parallel.
+    ///
+    /// The elements are collected into a buffer before being copied into the
+    /// array in bulk, so if either function panics, every element created so
+    /// far is dropped.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let array = HeapArray::with_reduced_label(100, |i| i as u64, |elements| {
+    ///     elements.par_iter().sum::<u64>()
+    /// });
+    /// assert!(*array.get_label() == 4950);
+    /// assert!(array[99] == 99);
+    /// ```
+    pub fn with_reduced_label<F, G>(len: usize, func: F, reduce: G) -> Self
+    where
+        F: Fn(usize) -> E + Sync + Send,
+        G: FnOnce(&[E]) -> L,
+    {
+        let mut elements: Vec<E> = (0..len).into_par_iter().map(func).collect();
+        let label = reduce(&elements);
+        unsafe {
+            let mut data = BaseArray::new_lazy(label, len);
+            ptr::copy_nonoverlapping(elements.as_ptr(), data.get_ptr_mut(0), len);
+            elements.set_len(0);
+            Self::from_base(data, len)
+        }
+    }
+}
+
+/// Lets `array.par_iter()` iterate over the elements of an array in parallel.
+impl<'a, E, L, P> IntoParallelIterator for &'a SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Sync,
+{
+    type Item = &'a E;
+    type Iter = rayon::slice::Iter<'a, E>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().par_iter()
+    }
+}
+
+/// Lets `array.par_iter_mut()` iterate over the elements of an array in
+/// parallel.
+impl<'a, E, L, P> IntoParallelIterator for &'a mut SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Send,
+{
+    type Item = &'a mut E;
+    type Iter = rayon::slice::IterMut<'a, E>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice_mut().par_iter_mut()
+    }
+}
+
+/// Lets `array.par_iter()` iterate over the elements of a reference-counted
+/// array in parallel.
+impl<'a, A, R, E, L> IntoParallelIterator for &'a RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArray<E>,
+    R: RefCounter<L>,
+    E: Sync + 'a,
+{
+    type Item = &'a E;
+    type Iter = rayon::slice::Iter<'a, E>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().par_iter()
+    }
+}
@@ -0,0 +1,103 @@
+//! Implementations of `Serialize` and `Deserialize` for the array types in this
+//! crate.
+//!
+//! Arrays are serialized as a struct with a `label` field and an `elements`
+//! sequence. Reference-counted arrays serialize the data they point to, and
+//! deserialize into a new array that isn't shared with anything.
+
+use crate::impls::{SafeArray, SafeArrayPtr};
+use crate::naive_rc::generic::RcArray;
+use crate::naive_rc::ref_counters::RefCounter;
+use crate::prelude::*;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The deserialized form of an array, before it's moved into a block.
+#[derive(Deserialize)]
+struct ArrayParts<E, L> {
+    label: L,
+    elements: Vec<E>,
+}
+
+impl<E, L> ArrayParts<E, L> {
+    /// Moves the label and elements into a new array of type `A`.
+    fn into_array<A>(self) -> A
+    where
+        A: LabelledArray<E, L>,
+    {
+        let len = self.elements.len();
+        let mut elements = self.elements.into_iter();
+        A::with_label(self.label, len, |_, _| elements.next().unwrap())
+    }
+}
+
+/// Serializes the label and elements of an array as a struct.
+fn serialize_parts<S, E, L>(serializer: S, label: &L, elements: &[E]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    E: Serialize,
+    L: Serialize,
+{
+    let mut state = serializer.serialize_struct("ArrayParts", 2)?;
+    state.serialize_field("label", label)?;
+    state.serialize_field("elements", elements)?;
+    state.end()
+}
+
+impl<E, L, P> Serialize for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Serialize,
+    L: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_parts(serializer, self.get_label(), self.as_slice())
+    }
+}
+
+impl<'de, E, L, P> Deserialize<'de> for SafeArray<E, L, P>
+where
+    P: SafeArrayPtr<E, L>,
+    E: Deserialize<'de>,
+    L: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ArrayParts::deserialize(deserializer).map(ArrayParts::into_array)
+    }
+}
+
+impl<A, R, E, L> Serialize for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R> + SliceArray<E>,
+    R: RefCounter<L>,
+    E: Serialize,
+    L: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_parts(serializer, self.get_label(), self.as_slice())
+    }
+}
+
+impl<'de, A, R, E, L> Deserialize<'de> for RcArray<A, R, E, L>
+where
+    A: LabelledArray<E, R>,
+    R: RefCounter<L>,
+    E: Deserialize<'de>,
+    L: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ArrayParts::deserialize(deserializer).map(ArrayParts::into_array)
+    }
+}
@@ -9,9 +9,73 @@ pub trait LabelledArray<E, L>: containers::CopyMap<usize, E> {
     /// Get a reference to the label.
     fn get_label(&self) -> &L;
 
+    /// Get a raw pointer to the label, to check whether an operation moved it.
+    ///
+    /// The label stays at the same address for as long as the array uses the
+    /// same block of memory, even if the array value itself is moved; accessing
+    /// the label or elements, including through `get_label_mut`, never moves it.
+    /// Clones of arrays that own their data get their own block, so their label
+    /// is at a different address, except when the block has a size of 0: those
+    /// aren't allocated, and all share the same dangling address. Clones of
+    /// reference-counted arrays share the label. Methods that resize an array,
+    /// like `SafeArray::truncate`, or that copy shared data, like
+    /// `RcArray::make_mut`, may move the label.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::with_label(0, 3, |_, i| i);
+    /// let ptr = array.label_ptr();
+    /// *array.get_label_mut() += 1;
+    /// let moved = array;
+    /// assert!(moved.label_ptr() == ptr);
+    /// assert!(moved.clone().label_ptr() != ptr);
+    ///
+    /// let empty = HeapArray::<(), ()>::new(0, |_| ());
+    /// assert!(empty.clone().label_ptr() == empty.label_ptr());
+    /// ```
+    fn label_ptr(&self) -> *const L {
+        self.get_label()
+    }
+
     /// Get a reference to the element at a specified index.
     /// Implementations of this method shouldn't do any safety checks.
+    ///
+    /// # Safety
+    /// `idx` must be less than the length of the array, like
+    /// `<[E]>::get_unchecked`.
     unsafe fn get_unchecked(&self, idx: usize) -> &E;
+
+    /// Runs destructors for the elements of this array, and for the label by
+    /// calling `drop_label` on it, and returns whether the memory they were
+    /// stored in is still allocated; if it is, it must later be freed using
+    /// `_dealloc`.
+    ///
+    /// Reference-counted arrays use this to destroy the contents of an array
+    /// while weak references to it still exist. They pass a `drop_label` that
+    /// only drops the data inside the reference counting struct, so that weak
+    /// references can keep using the counts until the memory is freed. Defaults
+    /// to dropping the whole array, without calling `drop_label`, and returning
+    /// `false`.
+    ///
+    /// # Safety
+    /// The array can't be used after calling this method, except to call
+    /// `_dealloc` if this method returned `true`. `drop_label` has to leave the
+    /// label in a state where it's safe to deallocate without dropping it.
+    unsafe fn _drop_contents<F>(&mut self, drop_label: F) -> bool
+    where
+        Self: Sized,
+        F: FnOnce(&mut L),
+    {
+        let _ = drop_label;
+        core::ptr::drop_in_place(self);
+        false
+    }
+
+    /// Deallocates the memory of this array without running any destructors.
+    ///
+    /// # Safety
+    /// Can only be called once, after `_drop_contents` has returned `true`.
+    unsafe fn _dealloc(&mut self) {}
 }
 
 /// Array with optional label struct stored next to the data that can
@@ -23,6 +87,23 @@ pub trait LabelledArrayMut<E, L>: LabelledArray<E, L> {
     /// Get a mutable reference to the element at a specified index.
     /// Implementations of this method shouldn't do any safety checks.
     unsafe fn get_mut_unchecked(&mut self, idx: usize) -> &mut E;
+
+    /// Get a mutable reference to the element at a specified index, without
+    /// checking that it's in bounds. Same as `get_mut_unchecked`, but named like
+    /// `<[E]>::get_unchecked_mut`.
+    ///
+    /// # Safety
+    /// `idx` must be less than the length of the array.
+    ///
+    /// ```rust
+    /// # use heaparray::*;
+    /// let mut array = HeapArray::new(3, |i| i);
+    /// unsafe { *array.get_unchecked_mut(1) = 10 };
+    /// assert!(unsafe { *array.get_unchecked(1) } == 10);
+    /// ```
+    unsafe fn get_unchecked_mut(&mut self, idx: usize) -> &mut E {
+        self.get_mut_unchecked(idx)
+    }
 }
 
 /// Trait for a labelled array with a default value.
@@ -2,12 +2,149 @@
 pub trait SliceArray<E> {
     /// Returns a reference to a slice into the elements of this array.
     fn as_slice(&self) -> &[E];
+
+    /// Divides the elements of this array into two slices at an index; the first
+    /// contains the elements at indices `0..mid`, and the second contains the
+    /// elements at indices `mid..len`.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    fn split_at(&self, mid: usize) -> (&[E], &[E]) {
+        self.as_slice().split_at(mid)
+    }
+
+    /// Returns an iterator over references to the elements of this array.
+    fn iter(&self) -> core::slice::Iter<'_, E> {
+        self.as_slice().iter()
+    }
+
+    /// Returns an iterator over every contiguous window of `size` elements in
+    /// this array. The windows overlap.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    fn windows(&self, size: usize) -> core::slice::Windows<'_, E> {
+        self.as_slice().windows(size)
+    }
+
+    /// Returns an iterator over `size` elements of this array at a time. The
+    /// last chunk is shorter if `size` doesn't divide the length of the array.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    fn chunks(&self, size: usize) -> core::slice::Chunks<'_, E> {
+        self.as_slice().chunks(size)
+    }
+
+    /// Returns whether this array has an element equal to `x`.
+    fn contains(&self, x: &E) -> bool
+    where
+        E: PartialEq,
+    {
+        self.as_slice().contains(x)
+    }
+
+    /// Searches this array, whose elements must be sorted, for `x`. Returns the
+    /// index of a matching element if one is found, or the index where `x`
+    /// could be inserted while keeping the array sorted otherwise.
+    fn binary_search(&self, x: &E) -> Result<usize, usize>
+    where
+        E: Ord,
+    {
+        self.as_slice().binary_search(x)
+    }
 }
 
 /// Array that returns a mutable slice into its contents
 pub trait SliceArrayMut<E> {
     /// Returns a mutable reference to a slice into the elements of this array.
     fn as_slice_mut(&mut self) -> &mut [E];
+
+    /// Divides the elements of this array into two mutable slices at an index;
+    /// the first contains the elements at indices `0..mid`, and the second
+    /// contains the elements at indices `mid..len`.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    fn split_at_mut(&mut self, mid: usize) -> (&mut [E], &mut [E]) {
+        self.as_slice_mut().split_at_mut(mid)
+    }
+
+    /// Returns an iterator over mutable references to the elements of this
+    /// array.
+    fn iter_mut(&mut self) -> core::slice::IterMut<'_, E> {
+        self.as_slice_mut().iter_mut()
+    }
+
+    /// Returns an iterator over mutable slices of `size` elements of this array
+    /// at a time. The last chunk is shorter if `size` doesn't divide the length
+    /// of the array.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    fn chunks_mut(&mut self, size: usize) -> core::slice::ChunksMut<'_, E> {
+        self.as_slice_mut().chunks_mut(size)
+    }
+
+    /// Rotates the elements of this array in place so that the element at index
+    /// `mid` becomes the first element.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    fn rotate_left(&mut self, mid: usize) {
+        self.as_slice_mut().rotate_left(mid)
+    }
+
+    /// Rotates the elements of this array in place so that the last `k` elements
+    /// become the first `k` elements.
+    ///
+    /// # Panics
+    /// Panics if `k > len`.
+    fn rotate_right(&mut self, k: usize) {
+        self.as_slice_mut().rotate_right(k)
+    }
+}
+
+/// Array whose elements can be reinitialized or reordered in place.
+///
+/// Implemented for `SafeArray`, and for reference-counted arrays, which panic if
+/// their data is shared.
+pub trait MutSliceOps<E> {
+    /// Replaces every element of this array with a clone of `value`, dropping
+    /// the old elements.
+    fn fill(&mut self, value: E)
+    where
+        E: Clone;
+
+    /// Replaces every element of this array with the result of calling `func`,
+    /// dropping the old elements.
+    fn fill_with<F>(&mut self, func: F)
+    where
+        F: FnMut() -> E;
+
+    /// Swaps the elements at indices `i` and `j`. Does nothing if `i == j`.
+    ///
+    /// # Panics
+    /// Panics if either index is out of bounds.
+    fn swap(&mut self, i: usize, j: usize);
+
+    /// Sorts the elements of this array, keeping the order of equal elements.
+    /// The label isn't changed.
+    fn sort(&mut self)
+    where
+        E: Ord;
+
+    /// Sorts the elements of this array with a comparator function, keeping the
+    /// order of equal elements. The label isn't changed.
+    fn sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&E, &E) -> core::cmp::Ordering;
+
+    /// Sorts the elements of this array, possibly reordering equal elements.
+    /// The label isn't changed.
+    fn sort_unstable(&mut self)
+    where
+        E: Ord;
 }
 
 /*
@@ -31,3 +31,38 @@ fn label_element_access() {
         }
     }
 }
+
+#[test]
+#[cfg_attr(feature = "checked-casts", should_panic)]
+fn incompatible_cast() {
+    // Empty, so that there's no block to leak when the cast panics.
+    let array = Array::<u64, ()>::new((), 0, |_, i| i as u64);
+    let cast = unsafe { array.cast_ref::<u8, NonNull<MemBlock<u8, ()>>>() };
+    assert!(!cast.is_null());
+}
+
+#[test]
+#[should_panic]
+fn assert_cast_compatible() {
+    let array = Array::<u64, ()>::new((), 0, |_, i| i as u64);
+    array.assert_cast_compatible::<u8>();
+}
+
+#[test]
+fn new_panic() {
+    let drops = AtomicUsize::new(0);
+    let info = before_alloc();
+    let result = std::panic::catch_unwind(|| {
+        Array::new(DropCounter(&drops), LENGTH, |_, i| {
+            if i == 3 {
+                // Unlike `panic!`, this skips the panic hook, which allocates
+                // while printing the message.
+                std::panic::resume_unwind(Box::new(()));
+            }
+            DropCounter(&drops)
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == 3 + 1);
+    after_alloc(result, info);
+}
@@ -0,0 +1,96 @@
+use crate::prelude::*;
+use heaparray::impls::*;
+
+#[test]
+fn push_pop() {
+    let info = before_alloc();
+    let mut array = GrowableArray::with_capacity(1);
+    for i in 0..LENGTH {
+        array.push(i.to_string());
+    }
+    assert!(array.len() == LENGTH);
+    assert!(array.capacity() >= LENGTH);
+    for i in (0..LENGTH).rev() {
+        assert!(array.pop() == Some(i.to_string()));
+    }
+    assert!(array.pop().is_none());
+    after_alloc(array, info);
+}
+
+#[test]
+fn drop_partial() {
+    let info = before_alloc();
+    let mut array = GrowableArray::with_label_capacity(String::from("label"), LENGTH * 2);
+    array.extend((0..LENGTH).map(|i| i.to_string()));
+    array.pop();
+    assert!(array.len() == LENGTH - 1);
+    assert!(array.capacity() == LENGTH * 2);
+    assert!(array.get_label() == "label");
+    after_alloc(array, info);
+}
+
+#[test]
+fn reserve() {
+    let info = before_alloc();
+    let mut array = GrowableArray::with_capacity(1);
+    array.push(Box::new(0));
+    array.reserve(LENGTH);
+    assert!(array.capacity() > LENGTH);
+    assert!(*array[0] == 0);
+    after_alloc(array, info);
+}
+
+#[test]
+fn extend_from_slice() {
+    let info = before_alloc();
+    let bytes: Vec<u8> = (0..LENGTH as u8).collect();
+    let mut array = GrowableArray::with_capacity(1);
+    array.extend_from_slice(&bytes);
+    array.extend_from_slice(&[]);
+    array.extend_from_slice(&bytes[..2]);
+    assert!(array.len() == LENGTH + 2);
+    assert!(array.as_slice()[..LENGTH] == bytes[..] && array.as_slice()[LENGTH..] == [0, 1]);
+    let strings: Vec<String> = (0..LENGTH).map(|i| i.to_string()).collect();
+    let mut cloned = GrowableArray::with_capacity(0);
+    cloned.extend_from_slice_cloned(&strings);
+    assert!(cloned.as_slice() == &strings[..]);
+    after_alloc((bytes, array, strings, cloned), info);
+}
+
+#[test]
+fn swap_remove() {
+    let info = before_alloc();
+    let mut array = GrowableArray::with_capacity(LENGTH);
+    array.extend((0..LENGTH).map(|i| i.to_string()));
+    assert!(array.swap_remove(2) == "2");
+    assert!(array[2] == (LENGTH - 1).to_string() && array.len() == LENGTH - 1);
+    assert!(array.swap_remove(LENGTH - 2) == (LENGTH - 2).to_string());
+    assert!(array.len() == LENGTH - 2);
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn swap_remove_out_of_bounds() {
+    let mut array = GrowableArray::with_capacity(1);
+    array.push(0);
+    array.swap_remove(1);
+}
+
+#[test]
+fn debug_elides_long_arrays() {
+    let mut array = GrowableArray::with_capacity(100);
+    for i in 0..100 {
+        array.push(i);
+    }
+    let debug = format!("{:.5?}", array);
+    assert!(debug.contains("len: 100"));
+    assert!(debug.contains("elements: [0, 1, 2, ..., 98, 99]"));
+}
+
+#[test]
+fn send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<GrowableArray<u8>>();
+    assert_send_sync::<GrowableArray<String, String>>();
+}
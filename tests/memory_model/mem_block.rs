@@ -78,3 +78,104 @@ pub fn ref_alloc_efficient() {
 // pub fn block_alignment() {
 //     let blk = MemBlock::<(), Vec<
 // }
+
+#[test]
+pub fn alloc_in_custom_allocator() {
+    use core::alloc::Layout;
+    use heaparray::base::{Global, RawAllocator};
+
+    struct CountingAllocator(AtomicUsize);
+
+    unsafe impl RawAllocator for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.0.fetch_add(layout.size(), Ordering::SeqCst);
+            Global.alloc(layout)
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            self.0.fetch_sub(layout.size(), Ordering::SeqCst);
+            Global.dealloc(ptr, layout)
+        }
+    }
+
+    let allocator = CountingAllocator(AtomicUsize::new(0));
+    let info = before_alloc();
+    unsafe {
+        let blk = HeapArrayMemBlock::<u64, u8>::alloc_in(10, &allocator);
+        let (size, _) = HeapArrayMemBlock::<u64, u8>::memory_layout(10);
+        assert!(allocator.0.load(Ordering::SeqCst) == size);
+        HeapArrayMemBlock::dealloc_in(blk, 10, &allocator);
+    }
+    assert!(allocator.0.load(Ordering::SeqCst) == 0);
+    after_alloc((), info);
+}
+
+#[test]
+pub fn realloc_keeps_contents() {
+    let info = before_alloc();
+    unsafe {
+        let mut blk = MemBlock::<u64, u8>::alloc(LENGTH);
+        core::ptr::write(blk.lbl_ptr(), 42);
+        for i in 0..LENGTH {
+            core::ptr::write(blk.elem_ptr(i), i as u64);
+        }
+        blk.realloc(LENGTH, LENGTH * 4);
+        assert!(*blk.lbl_ptr() == 42);
+        for i in 0..LENGTH {
+            assert!(*blk.elem_ptr(i) == i as u64);
+        }
+        blk.realloc(LENGTH * 4, 2);
+        assert!(*blk.lbl_ptr() == 42);
+        assert!(*blk.elem_ptr(0) == 0 && *blk.elem_ptr(1) == 1);
+        blk.realloc(2, 0);
+        assert!(*blk.lbl_ptr() == 42);
+        blk.realloc(0, 1);
+        assert!(*blk.lbl_ptr() == 42);
+        blk.dealloc(1);
+    }
+    after_alloc((), info);
+}
+
+#[test]
+pub fn checked_layout_overflow() {
+    let len = usize::MAX / core::mem::size_of::<u64>() + 1;
+    assert!(HeapArrayMemBlock::<u64, u8>::checked_memory_layout(len).is_none());
+    assert!(
+        HeapArrayMemBlock::<u64, u8>::checked_memory_layout(LENGTH)
+            == Some(HeapArrayMemBlock::<u64, u8>::memory_layout(LENGTH))
+    );
+    assert!(
+        HeapArrayMemBlock::<u64, u8>::checked_memory_layout(0)
+            == Some(HeapArrayMemBlock::<u64, u8>::memory_layout(0))
+    );
+    let result =
+        unsafe { HeapArrayMemBlock::<u64, u8>::try_alloc_in(len, &heaparray::base::Global) };
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn max_len_over_aligned_label() {
+    use core::alloc::Layout;
+
+    #[repr(align(64))]
+    struct Aligned;
+
+    fn check<E, L>() {
+        let max_len = HeapArrayMemBlock::<E, L>::max_len();
+        let (size, align) = HeapArrayMemBlock::<E, L>::memory_layout(max_len);
+        assert!(Layout::from_size_align(size, align).is_ok());
+        let (size, align) = HeapArrayMemBlock::<E, L>::memory_layout(max_len + 1);
+        assert!(Layout::from_size_align(size, align).is_err());
+    }
+
+    check::<u8, Aligned>();
+    check::<u8, u64>();
+    check::<u32, Aligned>();
+    check::<u64, u8>();
+}
+
+#[test]
+#[should_panic]
+pub fn memory_layout_overflow() {
+    let len = usize::MAX / core::mem::size_of::<u64>() + 1;
+    HeapArrayMemBlock::<u64, u8>::memory_layout(len);
+}
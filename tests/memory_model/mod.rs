@@ -1,3 +1,6 @@
 pub mod base_array;
+pub mod growable_array;
 pub mod mem_block;
+pub mod rc_array;
+pub mod safe_array;
 pub mod test_utils;
@@ -0,0 +1,379 @@
+use crate::prelude::*;
+use heaparray::naive_rc::ref_counters::RcStructWeak;
+use heaparray::*;
+
+#[test]
+fn update_shared() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    array.update(|elements| elements[0].push('!'));
+    assert!(!array.ref_eq(&other));
+    assert!(array[0] == "0!");
+    assert!(other[0] == "0");
+    after_alloc((array, other), info);
+}
+
+#[test]
+fn update_unique() {
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let first = &array[0] as *const String;
+    array.update(|elements| elements[0].push('!'));
+    assert!(core::ptr::eq(&array[0], first));
+    assert!(array[0] == "0!");
+}
+
+#[test]
+fn labelled_slice() {
+    let array = RcArray::with_label(String::from("label"), LENGTH, |_, i| i);
+    let slice = array.as_labelled_slice();
+    assert!(slice.get_label() == "label");
+    assert!(slice.as_slice() == array.as_slice());
+}
+
+#[test]
+fn hash_matches_eq() {
+    use std::collections::HashSet;
+    let a = RcArray::with_label(1, LENGTH, |_, i| i.to_string());
+    let b = RcArray::with_label(1, LENGTH, |_, i| i.to_string());
+    let relabelled = RcArray::with_label(2, LENGTH, |_, i| i.to_string());
+    assert!(a == b);
+    assert!(!a.ref_eq(&b));
+    assert!(a != relabelled);
+
+    let mut set = HashSet::new();
+    set.insert(a);
+    assert!(set.contains(&b));
+    assert!(!set.contains(&relabelled));
+}
+
+#[test]
+fn fill_unique() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    array.fill(String::from("a"));
+    array.fill_with(String::new);
+    assert!(array.as_slice().iter().all(String::is_empty));
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn fill_shared() {
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let _other = ArrayRef::clone(&array);
+    array.fill(String::new());
+}
+
+#[test]
+fn swap_unique() {
+    let mut array = RcArray::new(LENGTH, |i| i);
+    array.swap(2, 3);
+    assert!(array[2] == 3 && array[3] == 2);
+}
+
+#[test]
+fn copy_map_bounds() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    assert!(array.get(LENGTH - 1).is_some());
+    assert!(array.get(LENGTH).is_none());
+    assert!(array.get_mut(LENGTH).is_none());
+    assert!(array.insert(LENGTH, String::new()).is_none());
+    after_alloc(array, info);
+}
+
+#[test]
+fn label_ptr() {
+    let info = before_alloc();
+    let mut array = RcArray::with_label(String::from("label"), LENGTH, |_, i| i);
+    let ptr = array.label_ptr();
+    let other = ArrayRef::clone(&array);
+    assert!(other.label_ptr() == ptr);
+    array.make_mut();
+    assert!(array.label_ptr() != ptr && other.label_ptr() == ptr);
+    after_alloc((array, other), info);
+}
+
+#[test]
+fn copy_on_write() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    assert!(array.to_mut().is_none());
+    array.make_mut()[0] = String::from("changed");
+    assert!(other[0] == "0" && array.to_mut().is_some());
+    let other = other.to_owned().unwrap();
+    let shared = ArrayRef::clone(&array);
+    let owned = array.make_owned();
+    assert!(owned[0] == "changed" && shared.ref_count() == 1);
+    after_alloc((other, shared, owned), info);
+}
+
+#[test]
+fn get_mut_slice() {
+    let info = before_alloc();
+    let mut array = ArcArray::new(LENGTH, |i| i.to_string());
+    for elem in array.get_mut_slice(2..5).unwrap() {
+        elem.push('!');
+    }
+    assert!(array[4] == "4!" && array[5] == "5");
+    assert!(array.get_mut_slice(0..LENGTH).is_some());
+    assert!(array.get_mut_slice(LENGTH - 1..LENGTH + 1).is_none());
+    let weak = array.downgrade();
+    assert!(array.get_mut_slice(0..1).is_none());
+    after_alloc((array, weak), info);
+}
+
+#[test]
+fn try_insert() {
+    use heaparray::impls::InsertError;
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    assert!(array.try_insert(0, String::from("a")) == Ok(String::from("0")));
+    let out_of_bounds = Err((InsertError::OutOfBounds, String::from("b")));
+    assert!(array.try_insert(LENGTH, String::from("b")) == out_of_bounds);
+    let other = ArrayRef::clone(&array);
+    let shared = Err((InsertError::Shared, String::from("c")));
+    assert!(array.try_insert(1, String::from("c")) == shared);
+    assert!(array[0] == "a" && array[1] == "1");
+    after_alloc((array, other, out_of_bounds, shared), info);
+}
+
+#[test]
+fn sort_unique() {
+    let mut array = RcArray::new(LENGTH, |i| LENGTH - i);
+    array.sort();
+    assert!(array[0] == 1 && array[LENGTH - 1] == LENGTH);
+}
+
+#[test]
+#[should_panic]
+fn sort_shared() {
+    let mut array = RcArray::new(LENGTH, |i| LENGTH - i);
+    let _other = ArrayRef::clone(&array);
+    array.sort_unstable();
+}
+
+#[test]
+#[should_panic]
+fn swap_shared() {
+    let mut array = RcArray::new(LENGTH, |i| i);
+    let _other = ArrayRef::clone(&array);
+    array.swap(2, 3);
+}
+
+#[test]
+fn weak_upgrade() {
+    let info = before_alloc();
+    let array = ArcArray::new(LENGTH, |i| i.to_string());
+    let weak = array.downgrade();
+    let strong = weak.upgrade().unwrap();
+    assert!(strong.ref_eq(&array));
+    assert!(weak.strong_count() == 2);
+    drop(strong);
+    drop(array);
+    assert!(weak.upgrade().is_none());
+    after_alloc(weak, info);
+}
+
+#[test]
+fn strong_and_weak_counts() {
+    let info = before_alloc();
+    let array = ArcArray::new(LENGTH, |i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    let weak = array.downgrade();
+    let weak_other = weak.clone();
+    assert!(array.strong_count() == 2 && other.weak_count() == 2);
+    drop(weak_other);
+    assert!(array.weak_count() == 1 && weak.strong_count() == 2);
+    let rc = RcArray::new(LENGTH, |i| i);
+    assert!(rc.strong_count() == 1 && rc.weak_count() == 0);
+    after_alloc((array, other, weak, rc), info);
+}
+
+#[test]
+fn weak_drops_contents_early() {
+    let drops = AtomicUsize::new(0);
+    let info = before_alloc();
+    let array = ArcArray::with_label(DropCounter(&drops), LENGTH, |_, _| DropCounter(&drops));
+    let weak = array.downgrade();
+    let other = weak.clone();
+    drop(array);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+    assert!(weak.strong_count() == 0 && weak.upgrade().is_none());
+    drop(weak.clone());
+    drop(weak);
+    after_alloc(other, info);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+}
+
+#[test]
+fn send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ArcArray<String, String>>();
+    assert_send_sync::<HeapArray<String, String>>();
+    assert_send_sync::<heaparray::impls::ThinPtrArray<String, String>>();
+}
+
+#[test]
+fn to_mut_races_upgrade() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    let mut array = ArcArray::new(LENGTH, |i| i);
+    let other_holds_strong = Arc::new(AtomicBool::new(false));
+    for _ in 0..100 {
+        let weak = array.downgrade();
+        let holds = Arc::clone(&other_holds_strong);
+        let handle = std::thread::spawn(move || {
+            if let Some(strong) = weak.upgrade() {
+                holds.store(true, Ordering::SeqCst);
+                drop(weak);
+                for _ in 0..100 {
+                    assert!(strong[0] == 0);
+                }
+                holds.store(false, Ordering::SeqCst);
+            }
+        });
+        while !handle.is_finished() {
+            if array.to_mut().is_some() {
+                assert!(!other_holds_strong.load(Ordering::SeqCst));
+            }
+        }
+        handle.join().unwrap();
+        assert!(array.to_mut().is_some());
+    }
+}
+
+#[test]
+fn weak_blocks_mutation() {
+    let mut array = ArcArray::new(LENGTH, |i| i);
+    let weak = array.downgrade();
+    assert!(array.to_mut().is_none());
+    assert!(array.get_mut(0).is_none());
+    drop(weak);
+    assert!(array.to_mut().is_some());
+}
+
+#[test]
+fn weak_thin_ptr() {
+    use heaparray::naive_rc::TpArcArray;
+    let info = before_alloc();
+    let array = TpArcArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    let weak = array.downgrade();
+    assert!(weak.upgrade().unwrap().get_label() == "label");
+    drop(array);
+    assert!(weak.upgrade().is_none());
+    after_alloc(weak, info);
+}
+
+type WeakCountedArray<E> = heaparray::naive_rc::generic::RcArray<
+    impls::FatPtrArray<E, RcStructWeak<()>>,
+    RcStructWeak<()>,
+    E,
+>;
+
+#[test]
+fn weak_rc_struct_no_leak() {
+    let info = before_alloc();
+    let array = WeakCountedArray::new(LENGTH, |i| i.to_string());
+    let weak = array.downgrade();
+    assert!(weak.upgrade().unwrap()[1] == "1");
+    drop(array);
+    assert!(weak.upgrade().is_none());
+    after_alloc(weak, info);
+}
+
+#[test]
+fn weak_rc_struct_outlives_strong() {
+    let drops = AtomicUsize::new(0);
+    let info = before_alloc();
+    let array = WeakCountedArray::new(LENGTH, |_| DropCounter(&drops));
+    let weak = array.downgrade();
+    let strong = weak.upgrade().unwrap();
+    drop(array);
+    assert!(drops.load(Ordering::SeqCst) == 0);
+    drop(strong);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH);
+    let other = weak.clone();
+    drop(weak);
+    assert!(other.strong_count() == 0);
+    after_alloc(other, info);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let info = before_alloc();
+    let array = ArcArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    let json = serde_json::to_string(&array).unwrap();
+    let parsed: ArcArray<String, String> = serde_json::from_str(&json).unwrap();
+    assert!(parsed == array);
+    assert!(parsed.ref_count() == 1);
+    assert!(!parsed.ref_eq(&array));
+    after_alloc((json, array, other, parsed), info);
+}
+
+#[test]
+fn shrink_to() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    assert!(!array.shrink_to(2));
+    assert!(array.len() == LENGTH);
+    drop(other);
+    assert!(!array.shrink_to(LENGTH));
+    assert!(array.shrink_to(2));
+    assert!(array.as_slice() == ["0", "1"]);
+    assert!(array.ref_count() == 1);
+    after_alloc(array, info);
+}
+
+#[test]
+fn shrink_to_thin_ptr() {
+    use heaparray::naive_rc::TpArcArray;
+    let info = before_alloc();
+    let mut array = TpArcArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    assert!(array.shrink_to(3));
+    assert!(array.len() == 3);
+    assert!(array.get_label() == "label");
+    after_alloc(array, info);
+}
+
+#[test]
+fn clone_from_unique() {
+    let mut array = RcArray::new(LENGTH, |i| i);
+    let source = RcArray::new(LENGTH, |i| i * 2);
+    let info = before_alloc();
+    array.clone_from(&source);
+    let diff = crate::TEST_MONITOR.local_info().relative_to(&info);
+    assert!(diff.bytes_alloc == 0 && diff.bytes_dealloc == 0);
+    assert!(!array.ref_eq(&source));
+    assert!(array.ref_count() == 1 && source.ref_count() == 1);
+    assert!(array.as_slice() == source.as_slice());
+}
+
+#[test]
+fn clone_from_shared() {
+    let info = before_alloc();
+    let mut array = RcArray::new(LENGTH, |i| i.to_string());
+    let other = ArrayRef::clone(&array);
+    let source = RcArray::new(LENGTH / 2, |i| (i * 2).to_string());
+    array.clone_from(&source);
+    assert!(!array.ref_eq(&source) && !array.ref_eq(&other));
+    assert!(array.ref_count() == 1 && other.ref_count() == 1);
+    assert!(array.as_slice() == source.as_slice());
+    assert!(other[1] == "1");
+    after_alloc((array, other, source), info);
+}
+
+#[test]
+fn default_is_empty() {
+    let info = before_alloc();
+    let array = RcArray::<String, u32>::default();
+    assert!(array.len() == 0 && *array.get_label() == 0);
+    assert!(array.ref_count() == 1);
+    after_alloc(array, info);
+}
@@ -0,0 +1,1336 @@
+use crate::prelude::*;
+use heaparray::*;
+
+fn strings(len: usize) -> HeapArray<String, ()> {
+    HeapArray::new(len, |i| i.to_string())
+}
+
+fn drop_counts(len: usize) -> Vec<AtomicUsize> {
+    (0..len).map(|_| AtomicUsize::new(0)).collect()
+}
+
+fn dropped_once(counts: &[AtomicUsize]) -> bool {
+    counts.iter().all(|count| count.load(Ordering::SeqCst) == 1)
+}
+
+#[test]
+fn into_iter_rev() {
+    let info = before_alloc();
+    let array = strings(LENGTH);
+    let mut expected = LENGTH;
+    for elem in array.into_iter_rev() {
+        expected -= 1;
+        assert!(elem == expected.to_string());
+    }
+    assert!(expected == 0);
+    after_alloc((), info);
+}
+
+#[test]
+fn into_iter_rev_partial() {
+    let info = before_alloc();
+    let mut iter = strings(LENGTH).into_iter_rev();
+    assert!(iter.next() == Some((LENGTH - 1).to_string()));
+    assert!(iter.next() == Some((LENGTH - 2).to_string()));
+    after_alloc(iter, info);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED_NONZERO: AtomicUsize = AtomicUsize::new(0);
+
+struct Secret(u64);
+
+unsafe impl heaparray::impls::Zeroable for Secret {}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+        if self.0 != 0 {
+            DROPPED_NONZERO.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[test]
+fn zeroize_on_drop() {
+    use heaparray::impls::ZeroizeOnDrop;
+
+    let info = before_alloc();
+    let mut array = ZeroizeOnDrop::new(HeapArray::new(LENGTH, |i| Secret(i as u64 + 1)));
+    array[0] = Secret(LENGTH as u64);
+    array.as_slice_mut().swap(1, 2);
+    assert!(array[0].0 == LENGTH as u64 && array[1].0 == 3);
+    after_alloc(array, info);
+    assert!(DROPPED.load(Ordering::SeqCst) == LENGTH + 1);
+    assert!(DROPPED_NONZERO.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn checksum_detects_corruption() {
+    let mut array = HeapArray::with_checksum(LENGTH, |i| i.to_string());
+    assert!(array.verify_checksum());
+    let old = array.insert(LENGTH / 2, String::from("corrupted"));
+    assert!(old == Some((LENGTH / 2).to_string()));
+    assert!(!array.verify_checksum());
+    array.insert(LENGTH / 2, old.unwrap());
+    assert!(array.verify_checksum());
+}
+
+#[test]
+fn shared_label() {
+    let info = before_alloc();
+    let first = HeapArray::with_label(vec![1, 2, 3], LENGTH, |_, i| i);
+    let mut second = HeapArray::with_shared_label(&first, LENGTH * 2, |i| i.to_string());
+    assert!(first.get_label() == second.get_label());
+    assert!(second.len() == LENGTH * 2);
+    second.get_label_mut().push(4);
+    assert!(first.get_label() == &vec![1, 2, 3]);
+    assert!(second.get_label() == &vec![1, 2, 3, 4]);
+    after_alloc((first, second), info);
+}
+
+#[test]
+fn push() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(LENGTH, 0, |_, _| String::new());
+    let array = array.push("a".to_string()).push("b".to_string());
+    let array = array.push("c".to_string());
+    assert!(array.as_slice() == ["a", "b", "c"]);
+    assert!(*array.get_label() == LENGTH);
+    after_alloc(array, info);
+}
+
+#[test]
+fn split_tail() {
+    let info = before_alloc();
+    let (head, tail) = strings(LENGTH).split_tail(3);
+    assert!(head.len() == 3);
+    assert!(tail.len() == LENGTH - 3);
+    for (i, elem) in head.as_slice().iter().chain(tail.as_slice()).enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    after_alloc((head, tail), info);
+}
+
+#[test]
+#[should_panic]
+fn split_tail_out_of_bounds() {
+    let _ = strings(LENGTH).split_tail(LENGTH + 1);
+}
+
+#[test]
+fn drain() {
+    let info = before_alloc();
+    let (array, drained) = strings(LENGTH).drain(2..5);
+    assert!(array.len() == LENGTH - 3 && array[1] == "1" && array[2] == "5");
+    assert!(drained.len() == 3);
+    assert!(drained.rev().collect::<Vec<_>>() == ["4", "3", "2"]);
+    let (all, none) = strings(LENGTH).drain(LENGTH..LENGTH);
+    assert!(all.len() == LENGTH && none.len() == 0);
+    after_alloc((array, all, none), info);
+}
+
+#[test]
+fn drain_drops_once() {
+    let drops = drop_counts(LENGTH);
+    let array = HeapArray::<_, ()>::new(LENGTH, |i| DropCounter(&drops[i]));
+    let (array, mut drained) = array.drain(1..LENGTH - 1);
+    drop(drained.next());
+    assert!(dropped_once(&drops[1..2]));
+    drop(drained);
+    assert!(dropped_once(&drops[1..LENGTH - 1]));
+    assert!(drops[0].load(Ordering::SeqCst) == 0);
+    drop(array);
+    assert!(dropped_once(&drops));
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let _ = strings(LENGTH).drain(LENGTH - 1..LENGTH + 1);
+}
+
+#[test]
+fn from_index_map() {
+    use std::collections::BTreeMap;
+
+    let info = before_alloc();
+    let mut entries = BTreeMap::new();
+    entries.insert(2, "two".to_string());
+    entries.insert(5, "five".to_string());
+    let array = HeapArray::from_index_map(LENGTH, entries);
+    for (i, elem) in array.as_slice().iter().enumerate() {
+        match i {
+            2 => assert!(elem == "two"),
+            5 => assert!(elem == "five"),
+            _ => assert!(elem.is_empty()),
+        }
+    }
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn from_index_map_out_of_bounds() {
+    let _ = HeapArray::from_index_map(LENGTH, vec![(LENGTH, 1)]);
+}
+
+#[test]
+#[should_panic]
+fn longer_than_max_len() {
+    let _ = HeapArray::<u64, ()>::with_len((), HeapArray::<u64, ()>::max_len() + 1);
+}
+
+#[test]
+fn try_with_label_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        HeapArray::try_with_label(DropCounter(&drops), LENGTH, |_, i| {
+            if i == LENGTH / 2 {
+                panic!("failed to initialize element");
+            }
+            DropCounter(&drops)
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH / 2 + 1);
+}
+
+#[test]
+fn into_array() {
+    let info = before_alloc();
+    let array = strings(3).into_array::<3>().ok().unwrap();
+    assert!(array == ["0", "1", "2"]);
+    after_alloc(array, info);
+}
+
+#[test]
+fn into_array_wrong_len() {
+    let info = before_alloc();
+    let array = strings(3).into_array::<4>().err().unwrap();
+    assert!(array.len() == 3);
+    after_alloc(array, info);
+}
+
+/// Iterator that reports a length of `hint`, but actually yields `len` strings.
+struct WrongHint {
+    hint: usize,
+    len: usize,
+    idx: usize,
+}
+
+impl Iterator for WrongHint {
+    type Item = String;
+    fn next(&mut self) -> Option<String> {
+        if self.idx < self.len {
+            self.idx += 1;
+            Some((self.idx - 1).to_string())
+        } else {
+            None
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.hint, Some(self.hint))
+    }
+}
+
+#[test]
+fn from_iter() {
+    let info = before_alloc();
+    let fat: HeapArray<String, ()> = (0..LENGTH).map(|i| i.to_string()).collect();
+    let thin: impls::ThinPtrArray<String, ()> = fat.as_slice().iter().cloned().collect();
+    let filtered: HeapArray<String, ()> = fat
+        .as_slice()
+        .iter()
+        .filter(|s| *s != "3")
+        .cloned()
+        .collect();
+    assert!(fat.len() == LENGTH);
+    assert!(fat.as_slice() == thin.as_slice());
+    assert!(filtered.len() == LENGTH - 1);
+    after_alloc((fat, thin, filtered), info);
+}
+
+#[test]
+fn from_iter_wrong_hint() {
+    let info = before_alloc();
+    let short: HeapArray<String, ()> = WrongHint {
+        hint: 10,
+        len: 4,
+        idx: 0,
+    }
+    .collect();
+    let long: HeapArray<String, ()> = WrongHint {
+        hint: 4,
+        len: 10,
+        idx: 0,
+    }
+    .collect();
+    assert!(short.as_slice() == strings(4).as_slice());
+    assert!(long.as_slice() == strings(10).as_slice());
+    after_alloc((short, long), info);
+}
+
+#[test]
+fn labelled_slice() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    let slice = array.as_labelled_slice();
+    assert!(slice.get_label() == "label");
+    assert!(slice.len() == LENGTH);
+    assert!(slice[1] == "1");
+    assert!(slice.as_slice() == array.as_slice());
+    after_alloc(array, info);
+}
+
+fn hash_of<T: std::hash::Hash>(value: &T) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn hash_matches_eq() {
+    let info = before_alloc();
+    let a = HeapArray::with_label(1, LENGTH, |_, i| i.to_string());
+    let b = HeapArray::with_label(1, LENGTH, |_, i| i.to_string());
+    let relabelled = HeapArray::with_label(2, LENGTH, |_, i| i.to_string());
+    let shorter = HeapArray::with_label(1, LENGTH - 1, |_, i| i.to_string());
+    assert!(a == b);
+    assert!(hash_of(&a) == hash_of(&b));
+    assert!(a != relabelled);
+    assert!(hash_of(&a) != hash_of(&relabelled));
+    assert!(a != shorter);
+    assert!(hash_of(&a) != hash_of(&shorter));
+    after_alloc((a, b, relabelled, shorter), info);
+}
+
+#[test]
+fn ordering() {
+    let info = before_alloc();
+    let mut arrays = vec![
+        HeapArray::new(2, |i| i as u8 + 1),
+        HeapArray::new(3, |i| i as u8),
+        HeapArray::new(2, |i| i as u8),
+    ];
+    arrays.sort();
+    assert!(arrays[0].as_slice() == [0, 1]);
+    assert!(arrays[1].as_slice() == [0, 1, 2]);
+    assert!(arrays[2].as_slice() == [1, 2]);
+
+    let low = HeapArray::<u8, u8>::with_label(0, 1, |_, _| 9);
+    let high = HeapArray::<u8, u8>::with_label(1, 1, |_, _| 0);
+    assert!(low < high);
+    after_alloc((arrays, low, high), info);
+}
+
+#[test]
+fn from_vec_reuse() {
+    let info = before_alloc();
+    let vec: Vec<String> = (0..LENGTH).map(|i| i.to_string()).collect();
+    let ptr = vec.as_ptr();
+    let reuse_info = before_alloc();
+    let array = HeapArray::from_vec_reuse(vec);
+    let diff = crate::TEST_MONITOR.local_info().relative_to(&reuse_info);
+    assert!(diff.bytes_alloc == 0, "Diff is {:#?}", diff);
+    assert!(array.as_slice().as_ptr() == ptr);
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+    after_alloc(array, info);
+}
+
+#[test]
+fn from_vec_reuse_fallback() {
+    let info = before_alloc();
+    let mut vec = Vec::with_capacity(LENGTH * 2);
+    vec.extend((0..LENGTH).map(|i| i.to_string()));
+    let ptr = vec.as_ptr();
+    let array = HeapArray::from_vec_reuse(vec);
+    assert!(array.as_slice().as_ptr() != ptr);
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+    after_alloc(array, info);
+}
+
+#[test]
+fn from_vec_drops_once() {
+    let drops = AtomicUsize::new(0);
+    let mut vec = Vec::with_capacity(LENGTH * 2);
+    vec.extend((0..LENGTH).map(|_| DropCounter(&drops)));
+    let array = HeapArray::from(vec);
+    assert!(array.len() == LENGTH);
+    assert!(drops.load(Ordering::SeqCst) == 0);
+    drop(array);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH);
+}
+
+#[test]
+fn scatter() {
+    let info = before_alloc();
+    let parts = strings(LENGTH).scatter::<3>();
+    assert!(parts[0].as_slice() == ["0", "3", "6", "9"]);
+    assert!(parts[1].as_slice() == ["1", "4", "7"]);
+    assert!(parts[2].as_slice() == ["2", "5", "8"]);
+    after_alloc(parts, info);
+}
+
+#[test]
+#[should_panic]
+fn scatter_zero() {
+    strings(LENGTH).scatter::<0>();
+}
+
+#[test]
+fn boxed_slice() {
+    let info = before_alloc();
+    let boxed: Box<[String]> = (0..LENGTH).map(|i| i.to_string()).collect();
+    let ptr = boxed.as_ptr();
+    let array = HeapArray::from(boxed);
+    assert!(array.as_slice().as_ptr() == ptr);
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+    let boxed = array.into_boxed_slice();
+    assert!(boxed.as_ptr() == ptr);
+    after_alloc(boxed, info);
+}
+
+#[test]
+fn boxed_slice_zero_sized() {
+    let info = before_alloc();
+    let array = HeapArray::from(vec![(); LENGTH].into_boxed_slice());
+    assert!(array.len() == LENGTH);
+    let boxed = array.into_boxed_slice();
+    assert!(boxed.len() == LENGTH);
+    after_alloc(boxed, info);
+}
+
+#[test]
+fn gather_scatter() {
+    let info = before_alloc();
+    for len in 0..LENGTH {
+        let array = strings(len);
+        let gathered = HeapArray::gather(array.clone().scatter::<3>());
+        assert!(gathered == array);
+    }
+    after_alloc((), info);
+}
+
+#[test]
+#[should_panic]
+fn gather_uneven() {
+    HeapArray::gather([strings(1), strings(3)]);
+}
+
+#[test]
+fn clone_copy() {
+    let info = before_alloc();
+    let array =
+        HeapArray::<u64, String>::with_label(String::from("label"), LENGTH, |_, i| i as u64);
+    let copy = array.clone_copy();
+    assert!(copy == array);
+    assert!(copy.as_slice().as_ptr() != array.as_slice().as_ptr());
+    after_alloc((array, copy), info);
+}
+
+#[test]
+fn raw_ptrs() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    assert!(array.as_ptr() == array.as_slice().as_ptr());
+    assert!(core::ptr::eq(array.as_mut_ptr(), array.as_ptr()));
+    let thin = impls::ThinPtrArray::<String, ()>::new(LENGTH, |i| i.to_string());
+    assert!(thin.as_ptr() == thin.as_slice().as_ptr());
+    after_alloc((array, thin), info);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn with_reduced_label() {
+    use rayon::prelude::*;
+    // Allocations happen on other threads, so the allocation monitor can't be
+    // used here.
+    let array = HeapArray::with_reduced_label(
+        LENGTH,
+        |i| i.to_string(),
+        |elements| elements.par_iter().map(|s| s.len()).sum::<usize>(),
+    );
+    assert!(*array.get_label() == LENGTH);
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_iter() {
+    use rayon::prelude::*;
+    let len = 100_000;
+    let mut array = HeapArray::<u64, ()>::new(len, |i| i as u64);
+    array.par_iter_mut().for_each(|elem| *elem *= 2);
+    let sum: u64 = array.par_iter().sum();
+    assert!(sum == (len as u64) * (len as u64 - 1));
+    let rc = RcArray::new(len, |i| i as u64);
+    assert!(rc.par_iter().sum::<u64>() == sum / 2);
+}
+
+#[repr(align(64))]
+struct CacheLine {
+    _data: [u8; 64],
+}
+
+fn ptr_alignment<T>(ptr: *const T) -> usize {
+    1 << (ptr as usize).trailing_zeros()
+}
+
+#[test]
+fn alignment() {
+    let info = before_alloc();
+    let plain = HeapArray::<u8, ()>::new(LENGTH, |i| i as u8);
+    let aligned =
+        HeapArray::<u8, CacheLine>::with_label(CacheLine { _data: [0; 64] }, LENGTH, |_, i| {
+            i as u8
+        });
+    let thin = impls::ThinPtrArray::<u64, ()>::new(LENGTH, |i| i as u64);
+    assert!(plain.alignment() == 1);
+    assert!(aligned.alignment() == 64);
+    assert!(HeapArray::<u8, CacheLine>::elements_offset() == 64);
+    assert!(thin.alignment() == core::mem::align_of::<u64>());
+    assert!(ptr_alignment(plain.as_ptr()) >= plain.alignment());
+    assert!(ptr_alignment(aligned.as_ptr()) >= aligned.alignment());
+    assert!(ptr_alignment(thin.as_ptr()) >= thin.alignment());
+    after_alloc((plain, aligned, thin), info);
+}
+
+#[test]
+fn block_layout() {
+    let info = before_alloc();
+    let fat = HeapArray::<u64, u8>::with_label(0, LENGTH, |_, i| i as u64);
+    let thin = impls::ThinPtrArray::<u64, u8>::with_label(0, LENGTH, |_, i| i as u64);
+    let word = core::mem::size_of::<usize>();
+    let fat_offset = HeapArray::<u64, u8>::elements_offset();
+    let thin_offset = impls::ThinPtrArray::<u64, u8>::elements_offset();
+    assert!(fat.block_layout().size() == fat_offset + 8 * LENGTH);
+    assert!(fat.block_layout().align() == core::mem::align_of::<u64>());
+    assert!(thin_offset > word);
+    assert!(thin.block_layout().size() == thin_offset + 8 * LENGTH);
+    assert!(
+        HeapArray::<(), ()>::new(LENGTH, |_| ())
+            .block_layout()
+            .size()
+            == 0
+    );
+    after_alloc((fat, thin), info);
+}
+
+#[test]
+fn range_index() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    assert!(array[2..5] == strings(LENGTH).as_slice()[2..5]);
+    assert!(array[7..].len() == LENGTH - 7);
+    assert!(array[..3].len() == 3);
+    assert!(array[..].len() == LENGTH);
+    assert!(array[2..=4].len() == 3);
+    assert!(array[..=4].len() == 5);
+    array[1..3].swap(0, 1);
+    assert!(array[1] == "2" && array[2] == "1");
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn range_index_out_of_bounds() {
+    let array = strings(LENGTH);
+    let _ = &array[..=LENGTH];
+}
+
+#[test]
+fn empty_with_label() {
+    let info = before_alloc();
+    let fat = HeapArray::<String, String>::empty_with_label(String::from("fat"));
+    let thin = impls::ThinPtrArray::<String, String>::empty_with_label(String::from("thin"));
+    assert!(fat.len() == 0 && thin.len() == 0);
+    assert!(fat.get_label() == "fat");
+    assert!(thin.get_label() == "thin");
+    after_alloc((fat, thin), info);
+}
+
+#[test]
+fn split_at() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    {
+        let (left, right) = array.split_at(3);
+        assert!(left.len() == 3 && right.len() == LENGTH - 3);
+        assert!(right[0] == "3");
+    }
+    {
+        let (left, right) = array.split_at_mut(LENGTH / 2);
+        core::mem::swap(&mut left[0], &mut right[0]);
+    }
+    assert!(array[0] == (LENGTH / 2).to_string());
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn split_at_out_of_bounds() {
+    strings(LENGTH).split_at(LENGTH + 1);
+}
+
+#[test]
+fn fill() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    array.fill(String::from("a"));
+    assert!(array.as_slice().iter().all(|s| s == "a"));
+    let mut count = 0;
+    array.fill_with(|| {
+        count += 1;
+        count.to_string()
+    });
+    assert!(array[LENGTH - 1] == LENGTH.to_string());
+    after_alloc(array, info);
+}
+
+#[test]
+fn swap() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    array.swap(0, LENGTH - 1);
+    array.swap(1, 1);
+    assert!(array[0] == (LENGTH - 1).to_string() && array[LENGTH - 1] == "0");
+    assert!(array[1] == "1");
+    after_alloc(array, info);
+}
+
+#[test]
+fn sort() {
+    let info = before_alloc();
+    let mut array = HeapArray::with_label(String::from("label"), LENGTH, |_, i| (LENGTH - i) % 4);
+    array.sort();
+    assert!(array.as_slice().windows(2).all(|w| w[0] <= w[1]));
+    array.sort_by(|a, b| b.cmp(a));
+    assert!(array[0] == 3 && array[LENGTH - 1] == 0);
+    array.sort_unstable();
+    assert!(array[0] == 0 && array[LENGTH - 1] == 3);
+    assert!(array.get_label() == "label");
+    after_alloc(array, info);
+}
+
+#[test]
+fn search() {
+    let info = before_alloc();
+    let array = HeapArray::<usize, ()>::new(LENGTH, |i| i * 2);
+    assert!(array.contains(&4) && !array.contains(&5));
+    assert!(array.binary_search(&4) == Ok(2));
+    assert!(array.binary_search(&5) == Err(3));
+    let rc = RcArray::new(LENGTH, |i| i * 2);
+    assert!(rc.contains(&6) && rc.binary_search(&7) == Err(4));
+    after_alloc((array, rc), info);
+}
+
+#[test]
+fn windows_and_chunks() {
+    let info = before_alloc();
+    let mut array = HeapArray::<usize, ()>::new(LENGTH, |i| i);
+    assert!(array.windows(3).count() == LENGTH - 2);
+    assert!(array.windows(3).all(|w| w[0] + 2 == w[2]));
+    assert!(array.chunks(4).count() == 3);
+    assert!(array.chunks(4).last() == Some(&[8, 9][..]));
+    for chunk in array.chunks_mut(4) {
+        chunk.reverse();
+    }
+    assert!(array.as_slice()[..5] == [3, 2, 1, 0, 7]);
+    let rc = RcArray::new(LENGTH, |i| i);
+    assert!(rc.windows(LENGTH).count() == 1 && rc.chunks(LENGTH + 1).count() == 1);
+    after_alloc((array, rc), info);
+}
+
+#[test]
+fn eq_slices() {
+    let info = before_alloc();
+    let array = HeapArray::<usize, &str>::with_label("label", 3, |_, i| i);
+    assert!(array == [0, 1, 2] && array != [0, 1]);
+    let slice: &[usize] = &[0, 1, 2];
+    assert!(array == slice[..] && array == slice);
+    assert!(array == vec![0, 1, 2]);
+    let thin = impls::ThinPtrArray::<usize, ()>::new(3, |i| i);
+    assert!(thin == [0, 1, 2] && thin != [2, 1, 0]);
+    let rc = RcArray::new(3, |i| i);
+    let arc = ArcArray::new(3, |i| i);
+    assert!(rc == [0, 1, 2] && arc == vec![0, 1, 2] && rc != slice[..1]);
+    after_alloc((array, thin, rc, arc), info);
+}
+
+#[test]
+fn as_ref_as_mut() {
+    fn total<T: AsRef<[usize]>>(elements: T) -> usize {
+        elements.as_ref().iter().sum()
+    }
+
+    let info = before_alloc();
+    let mut array = HeapArray::<usize, ()>::new(LENGTH, |i| i);
+    AsMut::<[usize]>::as_mut(&mut array)[0] = LENGTH;
+    assert!(total(&array) == LENGTH * (LENGTH + 1) / 2);
+    let rc = RcArray::new(LENGTH, |i| i);
+    assert!(total(&rc) == LENGTH * (LENGTH - 1) / 2);
+    after_alloc((array, rc), info);
+}
+
+#[test]
+fn borrow_as_map_key() {
+    use std::collections::{HashMap, HashSet};
+    let info = before_alloc();
+    let mut map = HashMap::new();
+    map.insert(HeapArray::<u8, ()>::new(3, |i| i as u8), "first");
+    map.insert(HeapArray::<u8, ()>::new(2, |i| i as u8 + 1), "second");
+    assert!(map.get(&[0u8, 1, 2][..]) == Some(&"first"));
+    assert!(map.get(&[1u8, 2][..]) == Some(&"second"));
+    assert!(!map.contains_key(&[0u8, 1][..]));
+    let mut set = HashSet::new();
+    set.insert(RcArray::new(2, |i| i));
+    assert!(set.contains(&[0, 1][..]));
+    after_alloc((map, set), info);
+}
+
+#[test]
+fn with_elements_then_label() {
+    let info = before_alloc();
+    let array = HeapArray::with_elements_then_label(
+        LENGTH,
+        |i| i.to_string(),
+        |elements| elements.concat(),
+    );
+    assert!(*array.get_label() == "0123456789");
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+    after_alloc(array, info);
+}
+
+#[test]
+fn with_elements_then_label_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        HeapArray::<DropCounter, DropCounter>::with_elements_then_label(
+            LENGTH,
+            |_| DropCounter(&drops),
+            |_| panic!("label"),
+        )
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH);
+}
+
+#[test]
+fn copy_map_bounds() {
+    let info = before_alloc();
+    let mut fat = strings(LENGTH);
+    let mut thin = impls::ThinPtrArray::<String, ()>::new(LENGTH, |i| i.to_string());
+    assert!(fat.get(LENGTH - 1).is_some() && thin.get(LENGTH - 1).is_some());
+    assert!(fat.get(LENGTH).is_none() && thin.get(LENGTH).is_none());
+    assert!(fat.get_mut(LENGTH).is_none() && thin.get_mut(LENGTH).is_none());
+    assert!(fat.insert(LENGTH, String::new()).is_none());
+    assert!(thin.insert(LENGTH, String::new()).is_none());
+    assert!(fat == strings(LENGTH) && fat.as_slice() == thin.as_slice());
+    after_alloc((fat, thin), info);
+}
+
+#[test]
+fn into_raw_from_raw() {
+    let info = before_alloc();
+    let (ptr, len) = strings(LENGTH).into_raw();
+    let fat = unsafe { HeapArray::<String, ()>::from_raw(ptr, len) };
+    assert!(len == LENGTH && fat == strings(LENGTH));
+    let thin = impls::ThinPtrArray::<String, u8>::with_label(1, LENGTH, |_, i| i.to_string());
+    let (ptr, len) = thin.into_raw();
+    let thin = unsafe { impls::ThinPtrArray::<String, u8>::from_raw(ptr, len) };
+    assert!(*thin.get_label() == 1 && thin.as_slice() == fat.as_slice());
+    after_alloc((fat, thin), info);
+}
+
+#[test]
+fn leak() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let label = DropCounter(&DROPS);
+    let leaked = impls::ThinPtrArray::with_label(label, LENGTH, |_, _| DropCounter(&DROPS)).leak();
+    assert!(leaked.len() == LENGTH);
+    assert!(DROPS.load(Ordering::SeqCst) == 0);
+}
+
+#[test]
+fn relabel() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(1u64, LENGTH, |_, i| i.to_string());
+    let first = array.as_ptr();
+    let array = array.relabel(|label| label as i64 - 2);
+    assert!(*array.get_label() == -1 && array.as_ptr() == first);
+    let array = array.relabel(|label| label.to_string());
+    assert!(array.get_label() == "-1");
+    assert!(array.as_slice() == strings(LENGTH).as_slice());
+    after_alloc(array, info);
+}
+
+#[test]
+fn relabel_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        let array = HeapArray::with_label(DropCounter(&drops), LENGTH, |_, _| DropCounter(&drops));
+        array.relabel(|_| -> u8 { panic!("relabel") })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+}
+
+#[test]
+fn rotate() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    array.rotate_left(3);
+    assert!(array[0] == "3" && array[LENGTH - 3] == "0");
+    array.rotate_right(3);
+    assert!(array == strings(LENGTH));
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn swap_out_of_bounds() {
+    strings(LENGTH).swap(0, LENGTH);
+}
+
+#[test]
+fn new_pow2() {
+    let info = before_alloc();
+    for len_log2 in 0..8 {
+        let array = HeapArray::new_pow2(len_log2, |i| i.to_string());
+        assert!(array.len() == 1 << len_log2);
+        assert!(array.is_pow2_len());
+    }
+    assert!(!strings(LENGTH).is_pow2_len());
+    after_alloc((), info);
+}
+
+#[test]
+#[should_panic]
+fn new_pow2_overflow() {
+    HeapArray::new_pow2(usize::MAX.count_ones(), |i| i as u8);
+}
+
+#[test]
+fn promote_first_to_label() {
+    let info = before_alloc();
+    let array = strings(LENGTH).promote_first_to_label();
+    assert!(array.get_label() == "0");
+    assert!(array.len() == LENGTH - 1);
+    for (i, elem) in array.as_slice().iter().enumerate() {
+        assert!(*elem == (i + 1).to_string());
+    }
+    after_alloc(array, info);
+}
+
+#[test]
+#[should_panic]
+fn promote_first_to_label_empty() {
+    let _ = strings(0).promote_first_to_label();
+}
+
+#[test]
+fn unzip_new() {
+    let info = before_alloc();
+    let (nums, squares) = HeapArray::unzip_new(LENGTH, |i| (i.to_string(), i * i));
+    for i in 0..LENGTH {
+        assert!(nums[i] == i.to_string());
+        assert!(squares[i] == i * i);
+    }
+    after_alloc((nums, squares), info);
+}
+
+#[test]
+fn unzip_new_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        HeapArray::unzip_new(LENGTH, |i| {
+            if i == LENGTH / 2 {
+                panic!("failed to initialize element");
+            }
+            (DropCounter(&drops), DropCounter(&drops))
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH / 2 * 2);
+}
+
+#[test]
+fn get_many_mut() {
+    let mut array = strings(LENGTH);
+    {
+        let [a, b, c] = array.get_many_mut([4, 0, 2]).unwrap();
+        a.push('a');
+        b.push('b');
+        c.push('c');
+    }
+    assert!(array[4] == "4a" && array[0] == "0b" && array[2] == "2c");
+    assert!(array.get_many_mut([]).is_some());
+    assert!(array.get_many_mut([1, 2, 1]).is_none());
+    assert!(array.get_many_mut([0, LENGTH]).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    let json = serde_json::to_string(&array).unwrap();
+    let parsed: HeapArray<String, String> = serde_json::from_str(&json).unwrap();
+    assert!(parsed == array);
+    let thin: impls::ThinPtrArray<String, String> = serde_json::from_str(&json).unwrap();
+    assert!(thin.get_label() == "label");
+    assert!(thin.as_slice() == array.as_slice());
+    after_alloc((json, array, parsed, thin), info);
+}
+
+#[test]
+fn transpose_result() {
+    let info = before_alloc();
+    let array: HeapArray<Result<String, String>, ()> =
+        HeapArray::new(LENGTH, |i| Ok(i.to_string()));
+    let array = array.transpose_result().unwrap();
+    for (i, elem) in array.as_slice().iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    let mixed = HeapArray::new(LENGTH, |i| {
+        if i % 3 == 2 {
+            Err(format!("error {}", i))
+        } else {
+            Ok(i.to_string())
+        }
+    });
+    let err = mixed.transpose_result().unwrap_err();
+    assert!(err == "error 2");
+    after_alloc((array, err), info);
+}
+
+#[test]
+fn iter_mut() {
+    let mut array = strings(LENGTH);
+    for elem in array.iter_mut() {
+        elem.push('!');
+    }
+    assert!(array.iter().len() == LENGTH);
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == format!("{}!", i));
+    }
+}
+
+#[test]
+fn transpose_option() {
+    let info = before_alloc();
+    let array = HeapArray::new(LENGTH, |i| Some(i.to_string()));
+    let array = array.transpose_option().unwrap();
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    after_alloc(array, info);
+
+    let drops = AtomicUsize::new(0);
+    let info = before_alloc();
+    let mixed = HeapArray::new(LENGTH, |i| {
+        if i == LENGTH / 2 {
+            None
+        } else {
+            Some(DropCounter(&drops))
+        }
+    });
+    assert!(mixed.transpose_option().is_none());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH - 1);
+    after_alloc((), info);
+}
+
+#[test]
+fn filter_collect() {
+    let info = before_alloc();
+    let array = strings(LENGTH).filter_collect(|s| s.parse::<usize>().unwrap() % 3 == 0);
+    assert!(array.len() == LENGTH.div_ceil(3));
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == (i * 3).to_string());
+    }
+    after_alloc(array, info);
+}
+
+#[test]
+fn filter_collect_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        let mut calls = 0;
+        HeapArray::new(LENGTH, |_| DropCounter(&drops)).filter_collect(|_| {
+            calls += 1;
+            if calls == LENGTH / 2 {
+                panic!("predicate failed");
+            }
+            calls % 2 == 0
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH);
+}
+
+#[test]
+fn get_unchecked() {
+    let info = before_alloc();
+    let mut array = strings(LENGTH);
+    unsafe { array.get_unchecked_mut(1).push('!') };
+    assert!(unsafe { array.get_unchecked(1) } == "1!");
+    let rc = RcArray::new(LENGTH, |i| i.to_string());
+    let arc = ArcArray::new(LENGTH, |i| i.to_string());
+    assert!(unsafe { rc.get_unchecked(2) == arc.get_unchecked(2) });
+    after_alloc((array, rc, arc), info);
+}
+
+#[test]
+fn find_byte() {
+    let info = before_alloc();
+    let len = LENGTH * 10;
+    let array = HeapArray::<u8, ()>::new(len, |i| (i % 64) as u8);
+    for needle in 0..64 {
+        assert!(array.find_byte(needle) == Some(needle as usize));
+    }
+    assert!(array.find_byte(64).is_none() && array.find_byte(0xff).is_none());
+    let thin = impls::ThinPtrArray::<u8, u8>::with_label(0, len, |_, i| (i == len - 1) as u8);
+    assert!(thin.find_byte(1) == Some(len - 1));
+    for start in 0..16 {
+        let tail = HeapArray::<u8, ()>::new(len - start, |i| (i + start == len - 2) as u8);
+        assert!(tail.find_byte(1) == Some(len - 2 - start));
+    }
+    after_alloc((array, thin), info);
+}
+
+#[test]
+fn dedup() {
+    let info = before_alloc();
+    let array = HeapArray::<String, ()>::new(LENGTH, |i| (i / 3).to_string()).dedup();
+    assert!(array.len() == LENGTH.div_ceil(3));
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    let by_key = strings(LENGTH).dedup_by_key(|s| s.len());
+    assert!(by_key.as_slice() == ["0"]);
+    after_alloc((array, by_key), info);
+}
+
+#[test]
+fn dedup_drops_once() {
+    let drops = drop_counts(LENGTH);
+    let array = HeapArray::<_, ()>::new(LENGTH, |i| (i / 2, DropCounter(&drops[i])));
+    let array = array.dedup_by(|elem, last| elem.0 == last.0);
+    assert!(array.len() == LENGTH / 2);
+    for (i, count) in drops.iter().enumerate() {
+        assert!(count.load(Ordering::SeqCst) == i % 2);
+    }
+    drop(array);
+    assert!(dropped_once(&drops));
+}
+
+#[test]
+fn new_stateful_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        let mut created = 0;
+        HeapArray::new_stateful(&mut created, LENGTH, |created, _| {
+            if *created == LENGTH / 2 {
+                panic!("failed to create element");
+            }
+            *created += 1;
+            DropCounter(&drops)
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH / 2);
+}
+
+#[test]
+fn map() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(String::from("label"), LENGTH, |_, i| i);
+    let array = array.map(|i| i.to_string());
+    assert!(array.get_label() == "label");
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    let thin = impls::ThinPtrArray::with_label(String::from("thin"), LENGTH, |_, i| i.to_string());
+    let thin = thin.map(|s| s.len());
+    assert!(thin.get_label() == "thin");
+    assert!(thin.iter().sum::<usize>() == LENGTH);
+    after_alloc((array, thin), info);
+}
+
+#[test]
+fn map_panic() {
+    let drops = AtomicUsize::new(0);
+    let result = std::panic::catch_unwind(|| {
+        let array = HeapArray::with_label(DropCounter(&drops), LENGTH, |_, _| DropCounter(&drops));
+        array.map(|elem| {
+            if drops.load(Ordering::SeqCst) == LENGTH / 2 {
+                panic!("failed to map element");
+            }
+            drop(elem);
+        })
+    });
+    assert!(result.is_err());
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+}
+
+#[test]
+fn try_map() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(String::from("label"), LENGTH, |_, i| i.to_string());
+    let array = array.try_map(|s| s.parse::<usize>()).unwrap();
+    assert!(array.get_label() == "label");
+    for (i, elem) in array.iter().enumerate() {
+        assert!(*elem == i);
+    }
+    after_alloc(array, info);
+}
+
+#[test]
+fn try_map_err() {
+    let drops = AtomicUsize::new(0);
+    let mapped = AtomicUsize::new(0);
+    let info = before_alloc();
+    let array = HeapArray::with_label(DropCounter(&drops), LENGTH, |_, i| (i, DropCounter(&drops)));
+    let result = array.try_map(|(i, counter)| {
+        drop(counter);
+        if i == LENGTH / 2 {
+            Err(i.to_string())
+        } else {
+            Ok(DropCounter(&mapped))
+        }
+    });
+    assert!(result.err() == Some((LENGTH / 2).to_string()));
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+    assert!(mapped.load(Ordering::SeqCst) == LENGTH / 2);
+    after_alloc((), info);
+}
+
+#[test]
+fn into_iter() {
+    let info = before_alloc();
+    let collected: Vec<String> = strings(LENGTH).into_iter().collect();
+    for (i, elem) in collected.iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    let reversed: Vec<String> = strings(LENGTH).into_iter().rev().collect();
+    assert!(reversed.first().unwrap() == &(LENGTH - 1).to_string());
+    assert!(reversed.len() == LENGTH);
+    after_alloc((collected, reversed), info);
+}
+
+#[test]
+fn into_iter_both_ends() {
+    let drops = AtomicUsize::new(0);
+    let info = before_alloc();
+    let array = HeapArray::with_label(DropCounter(&drops), LENGTH, |_, i| (i, DropCounter(&drops)));
+    let mut iter = array.into_iter();
+    assert!(iter.len() == LENGTH);
+    assert!(iter.next().unwrap().0 == 0);
+    assert!(iter.next_back().unwrap().0 == LENGTH - 1);
+    assert!(iter.next_back().unwrap().0 == LENGTH - 2);
+    assert!(iter.len() == LENGTH - 3);
+    assert!(drops.load(Ordering::SeqCst) == 3);
+    after_alloc(iter, info);
+    assert!(drops.load(Ordering::SeqCst) == LENGTH + 1);
+}
+
+#[test]
+fn into_iter_zero_sized() {
+    let array = HeapArray::new(LENGTH, |_| ());
+    assert!(array.into_iter().rev().count() == LENGTH);
+}
+
+#[test]
+fn iter_zero_sized() {
+    let array = HeapArray::<(), ()>::new(5, |_| ());
+    assert!(array.iter().count() == 5);
+    assert!((&array).into_iter().count() == 5);
+    assert!(array.into_iter().count() == 5);
+
+    let mut array = impls::ThinPtrArray::<(), u8>::with_label(1, 5, |_, _| ());
+    assert!(array.iter_mut().count() == 5);
+    assert!(array.iter_with_label().count() == 5);
+    assert!(array.into_iter_rev().count() == 5);
+}
+
+#[test]
+fn owning_iter_size_hint() {
+    let mut iter = strings(LENGTH).into_iter();
+    assert!(iter.size_hint() == (LENGTH, Some(LENGTH)));
+    iter.next();
+    assert!(iter.size_hint() == (LENGTH - 1, Some(LENGTH - 1)));
+    let mut rev = strings(LENGTH).into_iter_rev();
+    rev.next();
+    assert!(rev.len() == LENGTH - 1);
+    let zero_sized = HeapArray::new(LENGTH, |_| ()).into_iter();
+    assert!(zero_sized.size_hint() == (LENGTH, Some(LENGTH)));
+}
+
+#[test]
+fn concat() {
+    let info = before_alloc();
+    let array = strings(3).concat(HeapArray::new(LENGTH - 3, |i| (i + 3).to_string()));
+    assert!(array == strings(LENGTH));
+    let empty = HeapArray::empty_with_label(()).concat(strings(LENGTH));
+    assert!(empty == strings(LENGTH));
+    let other = strings(LENGTH).concat(HeapArray::empty_with_label(()));
+    assert!(other == strings(LENGTH));
+    after_alloc((array, empty, other), info);
+}
+
+#[test]
+fn resized_with() {
+    let info = before_alloc();
+    let array = HeapArray::with_label(String::from("label"), 3, |_, i| i.to_string());
+    let grown = array.resized_with(LENGTH, |i| i.to_string());
+    assert!(grown.get_label() == "label");
+    for (i, elem) in grown.iter().enumerate() {
+        assert!(*elem == i.to_string());
+    }
+    let shrunk = grown.resized_with(1, |_| unreachable!());
+    assert!(shrunk.as_slice() == ["0"]);
+    after_alloc((array, grown, shrunk), info);
+}
+
+#[test]
+fn debug_elides_long_arrays() {
+    let array = HeapArray::<usize, ()>::new(100, |i| i);
+    let debug = format!("{:?}", array);
+    assert!(debug.contains("len: 100"));
+    assert!(debug.contains("28, ..., 97, 98, 99]"));
+    assert!(!debug.contains("29,"));
+    let debug = format!("{:.5?}", array);
+    assert!(debug.contains("elements: [0, 1, 2, ..., 98, 99]"));
+    let short = HeapArray::<f64, f64>::with_label(0.5, 2, |_, i| i as f64);
+    let debug = format!("{:.1?}", short);
+    assert!(debug == "Array { label: 0.5, len: 2, elements: [0.0, ...] }");
+}
+
+#[test]
+fn default_is_empty() {
+    let info = before_alloc();
+    let array = HeapArray::<String, ()>::default();
+    assert!(array.len() == 0);
+    assert!(array.as_slice().is_empty());
+    let labelled = impls::ThinPtrArray::<String, String>::default();
+    assert!(labelled.len() == 0 && labelled.get_label().is_empty());
+    let zero_sized = HeapArray::<(), ()>::new(LENGTH, |_| ());
+    assert!(zero_sized.len() == LENGTH);
+    after_alloc((array, labelled, zero_sized), info);
+}
+
+#[test]
+fn default_drops_no_elements() {
+    let drops = AtomicUsize::new(0);
+    let array = HeapArray::<DropCounter, ()>::default();
+    drop(array);
+    assert!(drops.load(Ordering::SeqCst) == 0);
+}
+
+#[test]
+fn slice_api_matches_across_pointers() {
+    fn reverse_all<A>(array: &mut A) -> usize
+    where
+        A: SliceArray<String> + SliceArrayMut<String>,
+    {
+        array.as_slice_mut().reverse();
+        array.as_slice().len()
+    }
+
+    let info = before_alloc();
+    let mut fat = strings(LENGTH);
+    let mut thin = impls::ThinPtrArray::<String, ()>::new(LENGTH, |i| i.to_string());
+    assert!(reverse_all(&mut fat) == LENGTH);
+    assert!(reverse_all(&mut thin) == LENGTH);
+    assert!(fat.as_slice() == thin.as_slice());
+    assert!(fat[0] == (LENGTH - 1).to_string());
+    after_alloc((fat, thin), info);
+}
+
+#[test]
+fn builder() {
+    let info = before_alloc();
+    let mut builder = HeapArray::builder("label".to_string(), LENGTH);
+    for i in (0..LENGTH).rev() {
+        builder.set(i, i.to_string());
+    }
+    builder.set(0, "replaced".to_string());
+    assert!(builder.is_set(0) && builder.len() == LENGTH);
+    let array = builder.build().unwrap();
+    assert!(array[0] == "replaced" && array[LENGTH - 1] == (LENGTH - 1).to_string());
+    assert!(array.get_label() == "label");
+    after_alloc(array, info);
+}
+
+#[test]
+fn builder_missing() {
+    let drops = AtomicUsize::new(0);
+    let label = AtomicUsize::new(0);
+    let mut builder = HeapArray::builder(DropCounter(&label), LENGTH);
+    builder.set(1, DropCounter(&drops));
+    builder.set(3, DropCounter(&drops));
+    builder.set(3, DropCounter(&drops));
+    assert!(drops.load(Ordering::SeqCst) == 1);
+    let missing = builder.build().err().unwrap();
+    assert!(missing.len() == LENGTH - 2);
+    assert!(missing[..3] == [0, 2, 4]);
+    assert!(drops.load(Ordering::SeqCst) == 3);
+    assert!(label.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn custom_allocator() {
+    use core::alloc::Layout;
+    use heaparray::base::{Global, RawAllocator};
+    use heaparray::impls::{FatArrayPtr, SafeArray, ThinArrayPtr};
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Default)]
+    struct CountingAllocator;
+
+    unsafe impl RawAllocator for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+            Global.alloc(layout)
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+            Global.dealloc(ptr, layout)
+        }
+    }
+
+    type Fat = SafeArray<u64, u8, FatArrayPtr<u64, u8, CountingAllocator>>;
+    type Thin = SafeArray<u64, u8, ThinArrayPtr<u64, u8, CountingAllocator>>;
+
+    let info = before_alloc();
+    let fat = Fat::with_label(1, 10, |_, i| i as u64);
+    let thin = Thin::with_label(1, 10, |_, i| i as u64);
+    let size = fat.block_layout().size() + thin.block_layout().size();
+    assert!(ALLOCATED.load(Ordering::SeqCst) == size);
+    let mut fat = fat;
+    fat.truncate(5);
+    assert!(fat.as_slice() == [0, 1, 2, 3, 4]);
+    let size = fat.block_layout().size() + thin.block_layout().size();
+    assert!(ALLOCATED.load(Ordering::SeqCst) == size);
+    mem::drop((fat, thin));
+    assert!(ALLOCATED.load(Ordering::SeqCst) == 0);
+    after_alloc((), info);
+}
+
+#[test]
+fn truncate_drop_panic() {
+    let drops = drop_counts(LENGTH);
+    let info = before_alloc();
+    let mut array = HeapArray::<_, ()>::new(LENGTH, |i| PanicOnDrop(&drops[i], i == 4));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| array.truncate(2)));
+    assert!(result.is_err());
+    assert!(array.len() == 2);
+    for (i, count) in drops.iter().enumerate() {
+        assert!(count.load(Ordering::SeqCst) == if i < 2 { 0 } else { 1 });
+    }
+    after_alloc((array, result), info);
+    assert!(dropped_once(&drops));
+}
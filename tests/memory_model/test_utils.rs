@@ -1,4 +1,5 @@
 pub use core::mem;
+pub use core::sync::atomic::{AtomicUsize, Ordering};
 
 pub const LENGTH: usize = 10;
 pub type Load = Large;
@@ -31,6 +32,28 @@ impl Default for Large {
     }
 }
 
+/// Increments the counter it references when it's dropped.
+pub struct DropCounter<'a>(pub &'a AtomicUsize);
+
+impl<'a> Drop for DropCounter<'a> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Increments the counter it references when it's dropped, and then panics if
+/// the flag is set. The panic skips the panic hook, which would allocate.
+pub struct PanicOnDrop<'a>(pub &'a AtomicUsize, pub bool);
+
+impl<'a> Drop for PanicOnDrop<'a> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        if self.1 {
+            std::panic::resume_unwind(Box::new(()));
+        }
+    }
+}
+
 pub fn before_alloc() -> interloc::AllocInfo {
     crate::TEST_MONITOR.local_info()
 }